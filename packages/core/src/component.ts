@@ -1,4 +1,10 @@
-import { createComponentVNode, VNode, Component, Props } from "inferno";
+import {
+  createComponentVNode,
+  VNode,
+  Component,
+  Props,
+  options,
+} from "inferno";
 import { VNodeFlags } from "inferno-vnode-flags";
 import { getCurrentObserver, Observer, Signal } from "./observation";
 import { syncBatch } from "./batch";
@@ -32,6 +38,42 @@ export class RaskStatelessComponent extends Component {
   }
 }
 
+// Stateless components the SWC plugin keeps as functions (statelessMode: "function") are
+// tagged with `rask = true`. They render through a RaskStatelessComponent class, created
+// once per function, so they still observe the state they read
+const statelessClasses = new WeakMap<
+  RaskStatelessFunctionComponent<any>,
+  typeof RaskStatelessComponent
+>();
+
+function getStatelessClass(fn: RaskStatelessFunctionComponent<any>) {
+  let StatelessClass = statelessClasses.get(fn);
+
+  if (!StatelessClass) {
+    StatelessClass = class extends RaskStatelessComponent {
+      renderFn = fn;
+    };
+    statelessClasses.set(fn, StatelessClass);
+  }
+
+  return StatelessClass;
+}
+
+const createVNodeHook = options.createVNode;
+
+options.createVNode = (vNode: VNode) => {
+  if (
+    vNode.flags & VNodeFlags.ComponentFunction &&
+    (vNode.type as any)?.rask === true
+  ) {
+    vNode.type = getStatelessClass(vNode.type as any);
+    vNode.flags =
+      (vNode.flags & ~VNodeFlags.ComponentFunction) | VNodeFlags.ComponentClass;
+  }
+
+  createVNodeHook?.(vNode);
+};
+
 let currentComponent: RaskStatefulComponent<any> | undefined;
 
 export function getCurrentComponent() {
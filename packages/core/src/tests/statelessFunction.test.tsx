@@ -0,0 +1,32 @@
+import { describe, it, expect } from "vitest";
+import { createState } from "../createState";
+import { render } from "../index";
+
+describe("Stateless function components", () => {
+  it("should observe state in functions tagged by the plugin", async () => {
+    let state: ReturnType<typeof createState<{ count: number }>>;
+
+    /** @rask-ignore */
+    function Count(props: { state: { count: number } }) {
+      return <span>{props.state.count}</span>;
+    }
+    // What the plugin emits with statelessMode: "function"
+    (Count as any).rask = true;
+
+    function Component() {
+      state = createState({ count: 0 });
+
+      return () => <Count state={state} />;
+    }
+
+    const container = document.createElement("div");
+    render(<Component />, container);
+
+    expect(container.textContent).toBe("0");
+
+    state.count = 1;
+    await new Promise((resolve) => setTimeout(resolve, 0));
+
+    expect(container.textContent).toBe("1");
+  });
+});
@@ -1,539 +1,3388 @@
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use swc_core::common::{comments::Comments, errors::HANDLER, Span, Spanned, DUMMY_SP};
 use swc_core::ecma::{
     ast::*,
-    atoms::Wtf8Atom,
-    utils::{private_ident, quote_ident},
-    visit::{noop_visit_mut_type, VisitMut, VisitMutWith},
+    atoms::{Atom, Wtf8Atom},
+    utils::{collect_decls, find_pat_ids, private_ident, quote_ident, IdentUsageFinder, StmtLike},
+    visit::{noop_visit_mut_type, noop_visit_type, Visit, VisitMut, VisitMutWith, VisitWith},
+};
+use swc_core::plugin::{
+    metadata::TransformPluginMetadataContextKind, plugin_transform, proxies::TransformPluginProgramMetadata,
 };
-use swc_core::plugin::{plugin_transform, proxies::TransformPluginProgramMetadata};
 
-#[derive(Default, Clone, serde::Deserialize)]
-#[serde(crate = "serde")]
-pub struct Config {
-    #[serde(default, rename = "importSource")]
-    pub import_source: Option<String>,
-}
+#[derive(Clone, serde::Deserialize)]
+#[serde(crate = "serde", default)]
+pub struct Config {
+    #[serde(rename = "importSource")]
+    pub import_source: Option<String>,
+    #[serde(rename = "statefulBaseClass")]
+    pub stateful_base_class: Option<String>,
+    #[serde(rename = "statelessBaseClass")]
+    pub stateless_base_class: Option<String>,
+    #[serde(rename = "setupProperty")]
+    pub setup_property: Option<String>,
+    #[serde(rename = "renderProperty")]
+    pub render_property: Option<String>,
+    #[serde(rename = "warnOnAmbiguous")]
+    pub warn_on_ambiguous: bool,
+    #[serde(rename = "forceExports")]
+    pub force_exports: bool,
+    #[serde(rename = "forceExportsPattern")]
+    pub force_exports_pattern: Option<String>,
+    #[serde(rename = "componentNamePattern")]
+    pub component_name_pattern: Option<String>,
+    #[serde(rename = "rewriteFrom")]
+    pub rewrite_from: Option<Vec<String>>,
+    #[serde(rename = "importMap")]
+    pub import_map: Option<HashMap<String, String>>,
+    #[serde(rename = "useMethods")]
+    pub use_methods: bool,
+    #[serde(rename = "reportOnly")]
+    pub report_only: bool,
+    pub verbose: bool,
+    #[serde(rename = "vnodeFactories")]
+    pub vnode_factories: Option<Vec<String>>,
+    #[serde(rename = "stripPrefix")]
+    pub strip_prefix: Option<String>,
+    #[serde(rename = "stripSuffix")]
+    pub strip_suffix: Option<String>,
+    #[serde(rename = "renderFromSetup")]
+    pub render_from_setup: bool,
+    #[serde(rename = "strictReturns")]
+    pub strict_returns: bool,
+    #[serde(rename = "rewriteImports")]
+    pub rewrite_imports: Option<bool>,
+    #[serde(rename = "unwrapHocs")]
+    pub unwrap_hocs: bool,
+    #[serde(rename = "statelessMode")]
+    pub stateless_mode: Option<String>,
+    #[serde(rename = "spreadCallsAsVNodes")]
+    pub spread_calls_as_vnodes: bool,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    #[serde(rename = "hoistClasses")]
+    pub hoist_classes: bool,
+    #[serde(rename = "pruneUnknownImports")]
+    pub prune_unknown_imports: Option<Vec<String>>,
+    #[serde(rename = "emitDisplayName")]
+    pub emit_display_name: bool,
+    #[serde(rename = "detectSideEffectRenders")]
+    pub detect_side_effect_renders: bool,
+    #[serde(rename = "typeOnlyImports")]
+    pub type_only_imports: bool,
+    #[serde(rename = "recognizeCreateElement")]
+    pub recognize_create_element: bool,
+    #[serde(rename = "emitConstructor")]
+    pub emit_constructor: bool,
+    #[serde(rename = "memoStateless")]
+    pub memo_stateless: bool,
+    #[serde(rename = "memoHelper")]
+    pub memo_helper: Option<String>,
+    #[serde(rename = "liftStaticAssignments")]
+    pub lift_static_assignments: bool,
+    #[serde(rename = "importStyle")]
+    pub import_style: Option<String>,
+    #[serde(rename = "emitManifestExport")]
+    pub emit_manifest_export: bool,
+    #[serde(rename = "minifyImports")]
+    pub minify_imports: bool,
+    #[serde(rename = "autoFragment")]
+    pub auto_fragment: bool,
+    #[serde(rename = "summaryFormat")]
+    pub summary_format: Option<String>,
+    #[serde(rename = "registerModule")]
+    pub register_module: Option<String>,
+    #[serde(rename = "keepOriginal")]
+    pub keep_original: bool,
+    #[serde(rename = "explicitOnly")]
+    pub explicit_only: bool,
+    /// Options not recognized by the plugin, reported as a warning (usually typos)
+    #[serde(flatten)]
+    pub unknown_options: HashMap<String, serde_json::Value>,
+}
+
+/// The defaults of every option, which the plugin config is deserialized on top of.
+/// Options left as `None` resolve to the default noted next to them.
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            import_source: None, // "rask-ui"
+            stateful_base_class: None, // "RaskStatefulComponent"
+            stateless_base_class: None, // "RaskStatelessComponent"
+            setup_property: None, // "setup"
+            render_property: None, // "renderFn"
+            warn_on_ambiguous: false,
+            force_exports: false,
+            force_exports_pattern: None, // DEFAULT_FORCE_EXPORTS_PATTERN
+            component_name_pattern: None, // DEFAULT_COMPONENT_NAME_PATTERN
+            rewrite_from: None, // ["inferno"]
+            import_map: None,
+            use_methods: false,
+            report_only: false,
+            verbose: false,
+            vnode_factories: None, // DEFAULT_VNODE_FACTORIES
+            strip_prefix: None,
+            strip_suffix: None,
+            render_from_setup: false,
+            strict_returns: false,
+            rewrite_imports: None, // true
+            unwrap_hocs: false,
+            stateless_mode: None, // "class"
+            spread_calls_as_vnodes: false,
+            include: None, // every file
+            exclude: None,
+            hoist_classes: false,
+            prune_unknown_imports: None,
+            emit_display_name: false,
+            detect_side_effect_renders: false,
+            type_only_imports: false, // only for declaration emit: classes can't extend a type import
+            recognize_create_element: false,
+            emit_constructor: false,
+            memo_stateless: false,
+            memo_helper: None, // required by memoStateless, the runtime has no default helper
+            lift_static_assignments: false,
+            import_style: None, // "named"
+            emit_manifest_export: false,
+            minify_imports: false,
+            auto_fragment: false,
+            summary_format: None, // "none"
+            register_module: None,
+            keep_original: false,
+            explicit_only: false,
+            unknown_options: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Parse the plugin config. Malformed config falls back to the defaults, but says so in
+    /// the build log.
+    pub fn from_json(json: &str) -> Config {
+        serde_json::from_str(json).unwrap_or_else(|err| {
+            emit_warning(
+                DUMMY_SP,
+                &format!("rask-ui: invalid plugin config ({}), using the defaults", err),
+            );
+            Config::default()
+        })
+    }
+
+    /// Module the runtime is imported from (defaults to `rask-ui`)
+    fn import_source(&self) -> &str {
+        self.import_source.as_deref().unwrap_or("rask-ui")
+    }
+
+    /// Name of the base class stateful components extend (defaults to `RaskStatefulComponent`)
+    fn stateful_base_class(&self) -> &str {
+        self.stateful_base_class
+            .as_deref()
+            .unwrap_or("RaskStatefulComponent")
+    }
+
+    /// Name of the base class stateless components extend (defaults to `RaskStatelessComponent`)
+    fn stateless_base_class(&self) -> &str {
+        self.stateless_base_class
+            .as_deref()
+            .unwrap_or("RaskStatelessComponent")
+    }
+
+    /// Name of the runtime helper `memoStateless` wraps render functions in, when it is on
+    fn memo_helper(&self) -> Option<&str> {
+        self.memo_helper.as_deref().filter(|_| self.memo_stateless)
+    }
+
+    /// Exports of the import source generated code may reference: the base classes and helpers
+    fn runtime_exports(&self) -> Vec<&str> {
+        let mut names = vec![self.stateful_base_class(), self.stateless_base_class(), "createFragment"];
+        names.extend(self.memo_helper());
+        names.extend(self.register_module.as_deref());
+        names
+    }
+
+    /// Whether the runtime is imported as a namespace (`importStyle: "namespace"`) instead of
+    /// through named imports (`"named"`, the default)
+    fn namespace_imports(&self) -> bool {
+        self.import_style.as_deref() == Some("namespace")
+    }
+
+    /// Whether stateless components stay functions (`statelessMode: "function"`) instead of
+    /// becoming classes (`"class"`, the default)
+    fn stateless_as_function(&self) -> bool {
+        self.stateless_mode.as_deref() == Some("function")
+    }
+
+    /// Whether inferno imports are rewritten to the import source at all (defaults to true)
+    fn rewrite_imports(&self) -> bool {
+        self.rewrite_imports.unwrap_or(true)
+    }
+
+    /// Check if imports from a module should be rewritten to the import source
+    /// (defaults to only `inferno`)
+    fn rewrites_source(&self, source: &str) -> bool {
+        match &self.rewrite_from {
+            Some(sources) => sources.iter().any(|s| s == source),
+            None => source == "inferno",
+        }
+    }
+
+    /// The source imports from `source` are rewritten to: its `importMap` target, or the
+    /// import source for the `rewriteFrom` sources
+    fn rewritten_source(&self, source: &str) -> Option<&str> {
+        if let Some(mapped) = self.import_map.as_ref().and_then(|import_map| import_map.get(source)) {
+            return Some(mapped);
+        }
+        self.rewrites_source(source).then(|| self.import_source())
+    }
+
+    /// Check if a function name creates VNodes (defaults to Inferno's factories and `createPortal`)
+    fn is_vnode_factory(&self, name: &str) -> bool {
+        match &self.vnode_factories {
+            Some(factories) => factories.iter().any(|factory| factory == name),
+            None => DEFAULT_VNODE_FACTORIES.contains(&name),
+        }
+    }
+
+    /// Strip the configured `stripPrefix`/`stripSuffix` from a component name. Returns
+    /// `None` when nothing was stripped or the result isn't a valid identifier.
+    fn strip_affixes(&self, name: &str) -> Option<String> {
+        let mut stripped = name;
+        if let Some(prefix) = self.strip_prefix.as_deref().filter(|prefix| !prefix.is_empty()) {
+            stripped = stripped.strip_prefix(prefix).unwrap_or(stripped);
+        }
+        if let Some(suffix) = self.strip_suffix.as_deref().filter(|suffix| !suffix.is_empty()) {
+            stripped = stripped.strip_suffix(suffix).unwrap_or(stripped);
+        }
+
+        if stripped == name || Ident::verify_symbol(stripped).is_err() {
+            return None;
+        }
+        Some(stripped.to_string())
+    }
+
+    /// Check if a file should be transformed: it must match one of the `include` globs
+    /// (when given) and none of the `exclude` globs
+    pub fn transforms_file(&self, filename: &str) -> bool {
+        let filename = filename.replace('\\', "/");
+        let matches_any = |globs: &[String]| globs.iter().any(|glob| glob_matches(glob, &filename));
+        self.include.as_deref().is_none_or(matches_any) && !self.exclude.as_deref().is_some_and(matches_any)
+    }
+
+    /// Property holding the setup function of stateful components (defaults to `setup`)
+    fn setup_property(&self) -> &str {
+        self.setup_property
+            .as_deref()
+            .filter(|name| Ident::verify_symbol(name).is_ok())
+            .unwrap_or("setup")
+    }
+
+    /// Property holding the render function of stateless components (defaults to `renderFn`)
+    fn render_property(&self) -> &str {
+        self.render_property
+            .as_deref()
+            .filter(|name| Ident::verify_symbol(name).is_ok())
+            .unwrap_or("renderFn")
+    }
+}
+
+/// VNode factory functions recognized when no `vnodeFactories` are configured
+const DEFAULT_VNODE_FACTORIES: &[&str] = &[
+    "createVNode",
+    "createComponentVNode",
+    "createFragment",
+    "createTextVNode",
+    "createPortal",
+    "cloneVNode",
+];
+
+/// React-style factories recognized with `recognizeCreateElement`: the classic pragma and the
+/// automatic JSX runtime. They only count when imported from a JSX runtime source, either
+/// as named imports (`import { jsx as _jsx }`) or through its namespace (`React.createElement`).
+const CREATE_ELEMENT_FACTORIES: &[&str] = &["createElement", "jsx", "jsxs", "jsxDEV"];
+
+/// Check if `recognizeCreateElement` factories may be imported from a source: React, Preact
+/// and their automatic runtimes
+fn is_jsx_runtime_source(source: &str) -> bool {
+    matches!(source, "react" | "preact")
+        || source.ends_with("/jsx-runtime")
+        || source.ends_with("/jsx-dev-runtime")
+}
+
+/// Names `forceExports` applies to when no `forceExportsPattern` is configured (PascalCase)
+const DEFAULT_FORCE_EXPORTS_PATTERN: &str = "^[A-Z][A-Za-z0-9]*$";
+
+/// Names considered for components when no `componentNamePattern` is configured
+const DEFAULT_COMPONENT_NAME_PATTERN: &str = "^[A-Z]";
+
+/// Leading comment directive that opts a declaration out of the transform
+const IGNORE_DIRECTIVE: &str = "@rask-ignore";
+
+/// Leading comment directives that force a declaration to a specific component kind
+const STATEFUL_DIRECTIVE: &str = "@rask-stateful";
+const STATELESS_DIRECTIVE: &str = "@rask-stateless";
+
+/// Class method the render arrow is lifted into with `renderFromSetup`. It can't be
+/// `render`, which would override the base class's own render
+const SETUP_RENDER_METHOD: &str = "renderView";
+
+/// Property set to `true` on stateless components kept as functions, so the runtime
+/// recognizes them
+const STATELESS_TAG_PROPERTY: &str = "rask";
+
+/// The kind of component a function was classified as
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ComponentKind {
+    /// Returns a render arrow, becomes a stateful component class
+    Stateful,
+    /// Directly returns VNodes, becomes a stateless component class
+    Stateless,
+}
+
+impl ComponentKind {
+    fn label(self) -> &'static str {
+        match self {
+            ComponentKind::Stateful => "stateful",
+            ComponentKind::Stateless => "stateless",
+        }
+    }
+}
+
+/// Where a candidate component function was declared
+#[derive(Clone, Copy)]
+struct DeclContext {
+    /// Span of the declaring statement, whose leading comments hold directives
+    span: Span,
+    /// Whether the declaration is exported
+    exported: bool,
+}
+
+/// Finds assignments to any of a set of bindings
+struct AssignmentFinder<'a> {
+    bindings: &'a [Ident],
+    found: bool,
+}
+
+impl AssignmentFinder<'_> {
+    fn is_binding(&self, ident: &Ident) -> bool {
+        self.bindings.iter().any(|binding| binding.to_id() == ident.to_id())
+    }
+}
+
+impl Visit for AssignmentFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        if let AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) = &assign.left {
+            self.found |= self.is_binding(&ident.id);
+        }
+        if let AssignTarget::Pat(pat) = &assign.left {
+            let targets: Vec<Ident> = find_pat_ids(pat);
+            self.found |= targets.iter().any(|target| self.is_binding(target));
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, update: &UpdateExpr) {
+        if let Expr::Ident(ident) = &*update.arg {
+            self.found |= self.is_binding(ident);
+        }
+        update.visit_children_with(self);
+    }
+}
+
+/// Finds the bindings a function body declares, in source order: `var`s, functions and
+/// classes at any block depth, but not the locals of nested functions
+#[derive(Default)]
+struct BodyBindingFinder {
+    found: Vec<Ident>,
+}
+
+impl Visit for BodyBindingFinder {
+    noop_visit_type!();
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        self.found.extend(find_pat_ids::<_, Ident>(&declarator.name));
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.found.push(fn_decl.ident.clone());
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.found.push(class_decl.ident.clone());
+    }
+
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/// Finds references to a binding that run while the module body is evaluated, i.e. outside
+/// of function bodies and instance class members
+struct EagerUsageFinder {
+    id: Id,
+    found: bool,
+}
+
+impl Visit for EagerUsageFinder {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.found |= ident.to_id() == self.id;
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    // The names of function declarations and expressions are bindings, not usages. This
+    // also keeps TypeScript overload signatures (`function App(x): VNode;`) from counting.
+    fn visit_fn_decl(&mut self, _: &FnDecl) {}
+
+    fn visit_fn_expr(&mut self, _: &FnExpr) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    // `export { App }` exports the binding itself, it doesn't read it
+    fn visit_export_named_specifier(&mut self, _: &ExportNamedSpecifier) {}
+
+    fn visit_constructor(&mut self, _: &Constructor) {}
+
+    fn visit_class_prop(&mut self, prop: &ClassProp) {
+        prop.key.visit_with(self);
+        if prop.is_static {
+            prop.value.visit_with(self);
+        }
+    }
+}
+
+/// Wraps arrays returned directly from a function body in a fragment factory call, for
+/// `autoFragment`. Without a factory it only records whether there is any.
+struct FragmentWrapper {
+    factory: Option<Expr>,
+    found: bool,
+}
+
+impl VisitMut for FragmentWrapper {
+    noop_visit_mut_type!();
+
+    fn visit_mut_function(&mut self, _: &mut Function) {}
+
+    fn visit_mut_arrow_expr(&mut self, _: &mut ArrowExpr) {}
+
+    fn visit_mut_class(&mut self, _: &mut Class) {}
+
+    fn visit_mut_return_stmt(&mut self, ret: &mut ReturnStmt) {
+        let Some(arg) = &mut ret.arg else {
+            return;
+        };
+        if !matches!(arg.unwrap_parens(), Expr::Array(_)) {
+            return;
+        }
+        self.found = true;
+        if let Some(factory) = &self.factory {
+            let children = std::mem::take(arg);
+            **arg = Expr::Call(CallExpr {
+                span: Default::default(),
+                ctxt: Default::default(),
+                callee: Callee::Expr(Box::new(factory.clone())),
+                args: vec![
+                    ExprOrSpread {
+                        spread: None,
+                        expr: children,
+                    },
+                    ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(Expr::Lit(Lit::Num(Number {
+                            span: Default::default(),
+                            value: 0.0,
+                            raw: None,
+                        }))),
+                    },
+                ],
+                type_args: None,
+            });
+        }
+    }
+}
+
+/// Export a CommonJS assignment target writes to: `Some(None)` for `module.exports` itself,
+/// `Some(Some(App))` for `exports.App` and `module.exports.App`
+fn commonjs_export_name(target: &MemberExpr) -> Option<Option<&IdentName>> {
+    let is_ident = |expr: &Expr, name: &str| matches!(expr, Expr::Ident(ident) if &*ident.sym == name);
+    let is_module_exports = |member: &MemberExpr| {
+        is_ident(&member.obj, "module") && matches!(&member.prop, MemberProp::Ident(prop) if &*prop.sym == "exports")
+    };
+
+    if is_module_exports(target) {
+        return Some(None);
+    }
+    let MemberProp::Ident(prop) = &target.prop else {
+        return None;
+    };
+    match &*target.obj {
+        Expr::Ident(ident) if &*ident.sym == "exports" => Some(Some(prop)),
+        Expr::Member(member) if is_module_exports(member) => Some(Some(prop)),
+        _ => None,
+    }
+}
+
+/// Short local name for the `index`th runtime import with `minifyImports`: `_a`, ..., `_z`,
+/// `_aa`, `_ab`, ...
+fn short_alias(index: usize) -> String {
+    let mut letters = vec![];
+    let mut index = index;
+    loop {
+        letters.push(char::from(b'a' + (index % 26) as u8));
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    std::iter::once('_').chain(letters.into_iter().rev()).collect()
+}
+
+/// Create `const <pattern> = require("<source>")`
+fn require_decl(pattern: Pat, source: &str) -> Stmt {
+    Stmt::Decl(Decl::Var(Box::new(VarDecl {
+        span: Default::default(),
+        ctxt: Default::default(),
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: Default::default(),
+            name: pattern,
+            init: Some(Box::new(Expr::Call(CallExpr {
+                span: Default::default(),
+                ctxt: Default::default(),
+                callee: Callee::Expr(Box::new(Expr::Ident(quote_ident!("require").into()))),
+                args: vec![ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Str(Str {
+                        span: Default::default(),
+                        value: Wtf8Atom::from(source),
+                        raw: None,
+                    }))),
+                }],
+                type_args: None,
+            }))),
+            definite: false,
+        }],
+    })))
+}
+
+/// Name of a (possibly parenthesized) named function expression: `function App() {}`
+fn fn_expr_ident(expr: &Expr) -> Option<Ident> {
+    match expr.unwrap_parens() {
+        Expr::Fn(fn_expr) => fn_expr.ident.clone(),
+        _ => None,
+    }
+}
+
+/// Create `constructor() { super(...arguments); }`, for `emitConstructor`
+fn super_constructor() -> ClassMember {
+    let super_call = Expr::Call(CallExpr {
+        span: Default::default(),
+        ctxt: Default::default(),
+        callee: Callee::Super(Super {
+            span: Default::default(),
+        }),
+        args: vec![ExprOrSpread {
+            spread: Some(DUMMY_SP),
+            expr: Box::new(Expr::Ident(quote_ident!("arguments").into())),
+        }],
+        type_args: None,
+    });
+
+    ClassMember::Constructor(Constructor {
+        span: Default::default(),
+        ctxt: Default::default(),
+        key: PropName::Ident(quote_ident!("constructor")),
+        params: vec![],
+        body: Some(BlockStmt {
+            span: Default::default(),
+            ctxt: Default::default(),
+            stmts: vec![Stmt::Expr(ExprStmt {
+                span: Default::default(),
+                expr: Box::new(super_call),
+            })],
+        }),
+        accessibility: None,
+        is_optional: false,
+    })
+}
+
+/// Create `static displayName = "App"`, naming a generated class for devtools
+fn display_name_member(display_name: Atom) -> ClassMember {
+    ClassMember::ClassProp(ClassProp {
+        span: Default::default(),
+        key: PropName::Ident(quote_ident!("displayName")),
+        value: Some(Box::new(Expr::Lit(Lit::Str(Str {
+            span: Default::default(),
+            value: Wtf8Atom::from(&*display_name),
+            raw: None,
+        })))),
+        type_ann: None,
+        is_static: true,
+        decorators: vec![],
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+        readonly: false,
+        declare: false,
+        definite: false,
+    })
+}
+
+/// Finds `this` in a function body, outside of nested functions and classes which bind their own
+struct ThisFinder {
+    found: bool,
+}
+
+impl Visit for ThisFinder {
+    noop_visit_type!();
+
+    fn visit_this_expr(&mut self, _: &ThisExpr) {
+        self.found = true;
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+/// Warn when an arrow component uses `this`. The arrow saw the enclosing `this`, but once
+/// it becomes the class's `setup`/`renderFn` function `this` is the component instance.
+fn warn_on_arrow_this(arrow: &ArrowExpr) {
+    let mut finder = ThisFinder { found: false };
+    arrow.body.visit_with(&mut finder);
+    if finder.found {
+        emit_warning(
+            arrow.span,
+            "rask-ui: arrow component uses `this`, which will be the component instance after the transform; write it as a function",
+        );
+    }
+}
+
+/// Statement list items that can hold a (possibly exported) class declaration
+trait ClassDeclItem: StmtLike {
+    fn class_decl_mut(&mut self) -> Option<&mut ClassDecl>;
+}
+
+impl ClassDeclItem for Stmt {
+    fn class_decl_mut(&mut self) -> Option<&mut ClassDecl> {
+        match self {
+            Stmt::Decl(Decl::Class(class_decl)) => Some(class_decl),
+            _ => None,
+        }
+    }
+}
+
+impl ClassDeclItem for ModuleItem {
+    fn class_decl_mut(&mut self) -> Option<&mut ClassDecl> {
+        match self {
+            ModuleItem::Stmt(stmt) => stmt.class_decl_mut(),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Class(class_decl),
+                ..
+            })) => Some(class_decl),
+            _ => None,
+        }
+    }
+}
+
+/// Turn `App.defaultProps = value;` into `static defaultProps = value`, taking the value out
+/// of the statement. Values using `this` are left alone, since a static initializer would
+/// see the class instead.
+fn take_static_assignment(stmt: &mut Stmt, class_ident: &Ident) -> Option<ClassMember> {
+    let Stmt::Expr(ExprStmt { expr, .. }) = stmt else {
+        return None;
+    };
+    let Expr::Assign(assign) = &mut **expr else {
+        return None;
+    };
+    let AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+        obj,
+        prop: MemberProp::Ident(prop),
+        ..
+    })) = &assign.left
+    else {
+        return None;
+    };
+    if assign.op != AssignOp::Assign || !matches!(&**obj, Expr::Ident(obj) if obj.to_id() == class_ident.to_id()) {
+        return None;
+    }
+    let mut finder = ThisFinder { found: false };
+    assign.right.visit_with(&mut finder);
+    if finder.found {
+        return None;
+    }
+
+    Some(ClassMember::ClassProp(ClassProp {
+        span: assign.span,
+        key: PropName::Ident(prop.clone()),
+        value: Some(std::mem::replace(&mut assign.right, Expr::undefined(DUMMY_SP))),
+        type_ann: None,
+        is_static: true,
+        decorators: vec![],
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+        readonly: false,
+        declare: false,
+        definite: false,
+    }))
+}
+
+/// Binding declared by a top-level class or single-declarator variable statement
+fn declared_binding(item: &ModuleItem) -> Option<Id> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+        _ => return None,
+    };
+    match decl {
+        Decl::Class(class_decl) => Some(class_decl.ident.to_id()),
+        Decl::Var(var_decl) if var_decl.decls.len() == 1 => match &var_decl.decls[0].name {
+            Pat::Ident(binding) => Some(binding.id.to_id()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Class declared by a top-level class or single-declarator `const App = class ...` statement
+fn declared_class(item: &ModuleItem) -> Option<&Class> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+        _ => return None,
+    };
+    match decl {
+        Decl::Class(class_decl) => Some(&class_decl.class),
+        Decl::Var(var_decl) if var_decl.decls.len() == 1 => match var_decl.decls[0].init.as_deref() {
+            Some(Expr::Class(class_expr)) => Some(&class_expr.class),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Check if declaring a class runs code that could read other bindings: static blocks and
+/// static initializers other than literals
+fn has_eager_static_members(class: &Class) -> bool {
+    class.body.iter().any(|member| match member {
+        ClassMember::ClassProp(prop) => {
+            prop.is_static && prop.value.as_deref().is_some_and(|value| !matches!(value, Expr::Lit(_)))
+        }
+        ClassMember::StaticBlock(_) => true,
+        _ => false,
+    })
+}
+
+/// Function a top-level TypeScript overload signature (`function App(x): VNode;`) belongs to.
+/// Signatures of functions that became component classes are left as they are, the
+/// TypeScript transform removes them.
+fn overload_signature(item: &ModuleItem) -> Option<Id> {
+    let (ident, function) = match item {
+        ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl)))
+        | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Fn(fn_decl),
+            ..
+        })) => (&fn_decl.ident, &fn_decl.function),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+            decl: DefaultDecl::Fn(FnExpr {
+                ident: Some(ident),
+                function,
+            }),
+            ..
+        })) => (ident, function),
+        _ => return None,
+    };
+    function.body.is_none().then(|| ident.to_id())
+}
+
+/// Check if a path matches a glob. `*` and `?` don't cross `/`, `**/` matches any number of
+/// directories. Globs are matched against the end of the path at a directory boundary, so
+/// `src/**` matches `/home/app/src/App.tsx`.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    let mut pattern = String::from("(^|/)");
+    let mut chars = glob.trim_start_matches("./").chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).is_ok_and(|regex| regex.is_match(path))
+}
+
+/// Name a named import specifier imports: `a` for both `{ a }` and `{ a as b }`
+fn imported_name(named: &ImportNamedSpecifier) -> Cow<'_, str> {
+    match &named.imported {
+        Some(imported) => export_name(imported),
+        None => Cow::Borrowed(&named.local.sym),
+    }
+}
+
+/// The name of a module export, which may be a string (`export { "a-b" as ab }`)
+fn export_name(name: &ModuleExportName) -> Cow<'_, str> {
+    match name {
+        ModuleExportName::Ident(ident) => Cow::Borrowed(&ident.sym),
+        ModuleExportName::Str(str) => str.value.to_string_lossy(),
+    }
+}
+
+/// Check if a statement is part of a directive prologue (`"use strict"`, `"use client"`, ...)
+fn is_directive(stmt: &Stmt) -> bool {
+    matches!(stmt, Stmt::Expr(ExprStmt { expr, .. }) if matches!(&**expr, Expr::Lit(Lit::Str(_))))
+}
+
+/// Number of leading statements forming the directive prologue. Generated code is inserted
+/// after them, since a directive only applies at the very top of the module or script.
+fn directive_prologue_len<'a>(stmts: impl IntoIterator<Item = Option<&'a Stmt>>) -> usize {
+    stmts
+        .into_iter()
+        .take_while(|stmt| stmt.is_some_and(is_directive))
+        .count()
+}
+
+/// Check if an expression is `null`, `undefined` or `void ...`
+fn is_empty_value(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(Lit::Null(_)) => true,
+        Expr::Ident(ident) => &*ident.sym == "undefined",
+        Expr::Unary(unary) => unary.op == UnaryOp::Void,
+        Expr::Paren(paren) => is_empty_value(&paren.expr),
+        _ => false,
+    }
+}
+
+/// Build the member expression reading a property of an object-literal registry:
+/// `components.App`, or `components["my-app"]` for keys that aren't identifiers
+fn registry_member(registry: &Ident, key: &PropName) -> Expr {
+    let prop = match key {
+        PropName::Ident(ident) => MemberProp::Ident(ident.clone()),
+        PropName::Str(str) => computed_prop(Expr::Lit(Lit::Str(str.clone()))),
+        PropName::Num(num) => computed_prop(Expr::Lit(Lit::Num(num.clone()))),
+        PropName::BigInt(big_int) => computed_prop(Expr::Lit(Lit::BigInt(big_int.clone()))),
+        PropName::Computed(computed) => computed_prop((*computed.expr).clone()),
+    };
+    Expr::Member(MemberExpr {
+        span: Default::default(),
+        obj: Box::new(Expr::Ident(registry.clone())),
+        prop,
+    })
+}
+
+fn computed_prop(expr: Expr) -> MemberProp {
+    MemberProp::Computed(ComputedPropName {
+        span: Default::default(),
+        expr: Box::new(expr),
+    })
+}
+
+/// Emit a warning through the SWC handler, if one is installed for the current transform
+fn emit_warning(span: Span, message: &str) {
+    if HANDLER.is_set() {
+        HANDLER.with(|handler| handler.struct_span_warn(span, message).emit());
+    }
+}
+
+/// Warn that `typeOnlyImports` imports the base class of the generated classes as a type
+fn warn_type_only_base_class(imported: &str) {
+    emit_warning(
+        DUMMY_SP,
+        &format!(
+            "rask-ui: typeOnlyImports imports `{}` as a type, but generated classes extend it at runtime; \
+             the option only suits declaration emit",
+            imported
+        ),
+    );
+}
+
+/// Emit a note through the SWC handler, if one is installed for the current transform
+fn emit_note(span: Span, message: &str) {
+    if HANDLER.is_set() {
+        HANDLER.with(|handler| handler.span_note_without_error(span, message));
+    }
+}
+
+pub struct RaskComponentTransform {
+    config: Config,
+    /// Named imports of the runtime exports the transform uses, as (imported name, local
+    /// binding): the ones the user already has and the ones `inject_runtime` adds
+    pending_imports: Vec<(String, Ident)>,
+    /// Local binding of `import * as Rask from "rask-ui"`, with `importStyle: "namespace"`
+    import_rask_namespace: Option<Ident>,
+    used_names: HashSet<Atom>,
+    /// Local bindings exported through `export { App }` / `export { App as default }`
+    exported_names: HashSet<Atom>,
+    force_exports_regex: Regex,
+    /// Names functions must match to be considered components, from `componentNamePattern`
+    component_name_regex: Regex,
+    comments: Option<Box<dyn Comments>>,
+    stateful_count: usize,
+    stateless_count: usize,
+    /// Functions matching both the stateful and stateless heuristics, for `summaryFormat`.
+    /// Counted during detection, which only borrows the transform.
+    ambiguous_count: Cell<usize>,
+    /// Name of the file being transformed, for `summaryFormat`
+    filename: Option<String>,
+    /// Names of the transformed components, for `emitManifestExport`
+    component_names: Vec<Atom>,
+    /// Classes generated for HOC-wrapped components, inserted before the current statement
+    hoisted_stmts: Vec<Stmt>,
+    /// Stateless components kept as functions, tagged after the current statement
+    tagged_functions: Vec<Expr>,
+    /// Bindings of the component classes generated so far
+    generated_classes: HashSet<Id>,
+    /// Functions kept for `keepOriginal` (`setup = AppImpl`) by this run or an earlier one,
+    /// which are left as they are
+    kept_originals: HashSet<Id>,
+    /// Bindings of the `recognizeCreateElement` factories imported from a JSX runtime
+    create_element_imports: HashSet<Id>,
+    /// Default and namespace imports of a JSX runtime (`React`), whose factory members are
+    /// recognized with `recognizeCreateElement`
+    jsx_runtime_namespaces: HashSet<Id>,
+}
+
+impl RaskComponentTransform {
+    /// Create the transform for use outside the plugin entrypoint, e.g. in a custom SWC pass pipeline
+    pub fn with_config(mut config: Config) -> Self {
+        if config
+            .import_source
+            .as_deref()
+            .is_some_and(|source| source.trim().is_empty())
+        {
+            emit_warning(DUMMY_SP, "rask-ui: importSource is empty, using `rask-ui`");
+            config.import_source = None;
+        }
+
+        if !config.unknown_options.is_empty() {
+            let mut keys: Vec<&str> = config.unknown_options.keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            emit_warning(
+                DUMMY_SP,
+                &format!("rask-ui: unknown config options `{}` are ignored", keys.join("`, `")),
+            );
+        }
+
+        if let Some(mode) = config
+            .stateless_mode
+            .as_deref()
+            .filter(|mode| !matches!(*mode, "class" | "function"))
+        {
+            emit_warning(
+                DUMMY_SP,
+                &format!("rask-ui: invalid statelessMode `{}`, using `class`", mode),
+            );
+            config.stateless_mode = None;
+        }
+
+        if let Some(format) = config
+            .summary_format
+            .as_deref()
+            .filter(|format| !matches!(*format, "none" | "text" | "json"))
+        {
+            emit_warning(
+                DUMMY_SP,
+                &format!("rask-ui: invalid summaryFormat `{}`, using `none`", format),
+            );
+            config.summary_format = None;
+        }
+
+        if config.memo_stateless {
+            match config.memo_helper.as_deref() {
+                Some(helper) if Ident::verify_symbol(helper).is_ok() => {}
+                Some(helper) => {
+                    emit_warning(
+                        DUMMY_SP,
+                        &format!("rask-ui: invalid memoHelper `{}`, memoStateless is off", helper),
+                    );
+                    config.memo_stateless = false;
+                }
+                None => {
+                    emit_warning(
+                        DUMMY_SP,
+                        "rask-ui: memoStateless needs memoHelper, the runtime has no memo helper; memoStateless is off",
+                    );
+                    config.memo_stateless = false;
+                }
+            }
+        }
+
+        if let Some(register) = config
+            .register_module
+            .as_deref()
+            .filter(|register| Ident::verify_symbol(register).is_err())
+        {
+            emit_warning(
+                DUMMY_SP,
+                &format!("rask-ui: invalid registerModule `{}`, modules aren't registered", register),
+            );
+            config.register_module = None;
+        }
+
+        if let Some(style) = config
+            .import_style
+            .as_deref()
+            .filter(|style| !matches!(*style, "named" | "namespace"))
+        {
+            emit_warning(
+                DUMMY_SP,
+                &format!("rask-ui: invalid importStyle `{}`, using `named`", style),
+            );
+            config.import_style = None;
+        }
+
+        let force_exports_regex = config
+            .force_exports_pattern
+            .as_deref()
+            .and_then(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(_) => {
+                    emit_warning(
+                        DUMMY_SP,
+                        &format!("rask-ui: invalid forceExportsPattern `{}`, using the default", pattern),
+                    );
+                    None
+                }
+            })
+            .unwrap_or_else(|| Regex::new(DEFAULT_FORCE_EXPORTS_PATTERN).unwrap());
+
+        let component_name_regex = config
+            .component_name_pattern
+            .as_deref()
+            .and_then(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(_) => {
+                    emit_warning(
+                        DUMMY_SP,
+                        &format!("rask-ui: invalid componentNamePattern `{}`, using the default", pattern),
+                    );
+                    None
+                }
+            })
+            .unwrap_or_else(|| Regex::new(DEFAULT_COMPONENT_NAME_PATTERN).unwrap());
+
+        RaskComponentTransform {
+            config,
+            pending_imports: Vec::new(),
+            import_rask_namespace: None,
+            used_names: HashSet::new(),
+            exported_names: HashSet::new(),
+            force_exports_regex,
+            component_name_regex,
+            comments: None,
+            stateful_count: 0,
+            stateless_count: 0,
+            ambiguous_count: Cell::new(0),
+            filename: None,
+            component_names: Vec::new(),
+            hoisted_stmts: vec![],
+            tagged_functions: vec![],
+            generated_classes: HashSet::new(),
+            kept_originals: HashSet::new(),
+            create_element_imports: HashSet::new(),
+            jsx_runtime_namespaces: HashSet::new(),
+        }
+    }
+
+    /// Number of components transformed into stateful component classes
+    pub fn stateful_count(&self) -> usize {
+        self.stateful_count
+    }
+
+    /// Number of components transformed into stateless component classes
+    pub fn stateless_count(&self) -> usize {
+        self.stateless_count
+    }
+
+    /// Set the name of the file being transformed, reported by `summaryFormat`
+    pub fn with_filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Attach the program's comments so directive comments like `@rask-ignore` can be read
+    pub fn with_comments<C: Comments + 'static>(mut self, comments: C) -> Self {
+        self.comments = Some(Box::new(comments));
+        self
+    }
+
+    /// Check if the leading comments at a span contain the given directive
+    fn has_directive(&self, span: Span, directive: &str) -> bool {
+        let Some(comments) = &self.comments else {
+            return false;
+        };
+        comments
+            .get_leading(span.lo)
+            .map(|leading| {
+                leading.iter().any(|comment| {
+                    comment
+                        .text
+                        .split(|c: char| c.is_whitespace() || c == '*')
+                        .any(|word| word == directive)
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Check if a callee is one of the configured VNode factories, or a factory imported from
+    /// a JSX runtime with `recognizeCreateElement`
+    fn is_vnode_factory(&self, ident: &Ident) -> bool {
+        self.config.is_vnode_factory(&ident.sym) || self.create_element_imports.contains(&ident.to_id())
+    }
+
+    /// Check if a member callee is a namespaced VNode factory: `Inferno.createVNode`, or
+    /// `React.createElement` on an import of a JSX runtime with `recognizeCreateElement`
+    fn is_vnode_factory_member(&self, member: &MemberExpr) -> bool {
+        let MemberProp::Ident(prop) = &member.prop else {
+            return false;
+        };
+        self.config.is_vnode_factory(&prop.sym)
+            || (CREATE_ELEMENT_FACTORIES.contains(&&*prop.sym)
+                && matches!(&*member.obj, Expr::Ident(obj) if self.jsx_runtime_namespaces.contains(&obj.to_id())))
+    }
+
+    /// Check if an expression contains a VNode-related call (recursive deep search)
+    fn has_vnode_call(&self, expr: &Expr) -> bool {
+        match expr {
+            // Direct VNode call - this is what we're looking for
+            Expr::Call(call) => {
+                if let Callee::Expr(callee_expr) = &call.callee {
+                    match &**callee_expr {
+                        Expr::Ident(ident) if self.is_vnode_factory(ident) => return true,
+                        Expr::Member(member) => {
+                            // Namespaced factory: Inferno.createVNode(...)
+                            if self.is_vnode_factory_member(member) {
+                                return true;
+                            }
+                            // Method chains: createVNode(...).method(), items.map(...).filter(...)
+                            if self.has_vnode_call(&member.obj) {
+                                return true;
+                            }
+                        }
+                        // Immediately-invoked functions: (() => createVNode(...))()
+                        callee => match callee.unwrap_parens() {
+                            Expr::Arrow(arrow) if self.is_render_arrow(arrow) => return true,
+                            Expr::Fn(fn_expr) => {
+                                if let Some(body) = &fn_expr.function.body {
+                                    if self.block_has_vnode_return(body) {
+                                        return true;
+                                    }
+                                }
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+                // Check arguments - important for .map(...), .filter(...), etc.
+                for arg in &call.args {
+                    if self.has_vnode_call(&arg.expr) {
+                        return true;
+                    }
+                }
+                false
+            }
+            // Constructor arguments: new Wrapper(createVNode(...)), `new Wrapper` has none
+            Expr::New(new_expr) => new_expr
+                .args
+                .iter()
+                .flatten()
+                .any(|arg| self.has_vnode_call(&arg.expr)),
+
+            // Parenthesized expressions
+            Expr::Paren(paren) => self.has_vnode_call(&paren.expr),
+
+            // Conditional (ternary): condition ? consequent : alternate
+            Expr::Cond(cond) => {
+                self.has_vnode_call(&cond.test) || self.has_vnode_call(&cond.cons) || self.has_vnode_call(&cond.alt)
+            }
+
+            // Logical: expr1 && expr2, expr1 || expr2
+            Expr::Bin(bin) => {
+                self.has_vnode_call(&bin.left) || self.has_vnode_call(&bin.right)
+            }
+
+            // Arrays: [expr1, expr2, ...]. With `spreadCallsAsVNodes`, spreading any call
+            // counts too, since `[...getVNodes()]` can't be checked across the call
+            Expr::Array(arr) => {
+                arr.elems.iter().any(|elem| {
+                    elem.as_ref()
+                        .map(|e| {
+                            self.has_vnode_call(&e.expr)
+                                || (e.spread.is_some()
+                                    && self.config.spread_calls_as_vnodes
+                                    && matches!(e.expr.unwrap_parens(), Expr::Call(_)))
+                        })
+                        .unwrap_or(false)
+                })
+            }
+
+            // Objects: { header: createVNode(...), ...slots }
+            Expr::Object(obj) => obj.props.iter().any(|prop| match prop {
+                PropOrSpread::Prop(prop) => match &**prop {
+                    Prop::KeyValue(key_value) => self.has_vnode_call(&key_value.value),
+                    _ => false,
+                },
+                PropOrSpread::Spread(spread) => self.has_vnode_call(&spread.expr),
+            }),
+
+            // Arrow functions: (args) => body
+            Expr::Arrow(arrow) => self.is_render_arrow(arrow),
+
+            // Member expressions: obj.method(), obj[expr]
+            Expr::Member(member) => self.member_has_vnode_call(member),
+
+            // Optional chaining: data?.items, data?.render?.(createVNode(...))
+            Expr::OptChain(opt_chain) => match &*opt_chain.base {
+                OptChainBase::Member(member) => self.member_has_vnode_call(member),
+                OptChainBase::Call(call) => {
+                    matches!(&*call.callee, Expr::Ident(ident) if self.is_vnode_factory(ident))
+                        || self.has_vnode_call(&call.callee)
+                        || call.args.iter().any(|arg| self.has_vnode_call(&arg.expr))
+                }
+            },
+
+            // Unary expressions: !expr, +expr, etc.
+            Expr::Unary(unary) => self.has_vnode_call(&unary.arg),
+
+            // Await expressions: await expr
+            Expr::Await(await_expr) => self.has_vnode_call(&await_expr.arg),
+
+            // Yield expressions: yield createVNode(...), `yield` alone has no argument
+            Expr::Yield(yield_expr) => yield_expr.arg.as_ref().is_some_and(|arg| self.has_vnode_call(arg)),
+
+            // Sequence expressions: (expr1, expr2, ...)
+            Expr::Seq(seq) => seq.exprs.iter().any(|e| self.has_vnode_call(e)),
+
+            // Template literals: `${createVNode(...)}`
+            Expr::Tpl(tpl) => tpl.exprs.iter().any(|e| self.has_vnode_call(e)),
+
+            // Tagged templates: html`<div>${expr}</div>`, with the tag as a configured factory
+            Expr::TaggedTpl(tagged) => {
+                let tag_is_factory = match &*tagged.tag {
+                    Expr::Ident(ident) => self.is_vnode_factory(ident),
+                    Expr::Member(member) => self.is_vnode_factory_member(member),
+                    _ => false,
+                };
+                tag_is_factory || tagged.tpl.exprs.iter().any(|e| self.has_vnode_call(e))
+            }
+
+            // TypeScript wrappers: expr as T, expr!, expr as const, expr satisfies T
+            Expr::TsAs(ts_as) => self.has_vnode_call(&ts_as.expr),
+            Expr::TsNonNull(non_null) => self.has_vnode_call(&non_null.expr),
+            Expr::TsConstAssertion(const_assertion) => self.has_vnode_call(&const_assertion.expr),
+            Expr::TsSatisfies(satisfies) => self.has_vnode_call(&satisfies.expr),
+
+            // JSX that hasn't been lowered yet, when running before the Inferno JSX transform
+            Expr::JSXElement(_) | Expr::JSXFragment(_) => true,
+
+            _ => false,
+        }
+    }
+
+    /// Check if a member expression's object or computed property contains a VNode call
+    fn member_has_vnode_call(&self, member: &MemberExpr) -> bool {
+        if self.has_vnode_call(&member.obj) {
+            return true;
+        }
+        match &member.prop {
+            MemberProp::Computed(computed) => self.has_vnode_call(&computed.expr),
+            _ => false,
+        }
+    }
+
+    /// Check if a function body directly returns VNode calls (stateless component)
+    ///
+    /// With `strictReturns`, every reachable return must produce a VNode or be empty
+    /// (`null`/`undefined`), and at least one must produce a VNode.
+    fn is_stateless_component(&self, func: &Function) -> bool {
+        if self.config.strict_returns {
+            let Some(body) = &func.body else {
+                return false;
+            };
+            let mut found = false;
+            return self.reachable_returns_are_vnodes(&body.stmts, body, &mut found) && found;
+        }
+
+        if let Some(body) = &func.body {
+            for stmt in &body.stmts {
+                if self.stmt_returns_vnode(stmt, body) {
+                    return true;
+                }
+                // Generator components yield their VNodes: function* App() { yield createVNode(...) }
+                if func.is_generator && self.stmt_yields_vnode(stmt) {
+                    return true;
+                }
+            }
+            return self.renders_by_side_effect(body);
+        }
+        false
+    }
+
+    /// Check if a block without a `return` renders through a call instead, for
+    /// `detectSideEffectRenders`: `() => { doRender(createVNode(...)) }`.
+    /// Only the top-level expression statements are looked at.
+    fn renders_by_side_effect(&self, block: &BlockStmt) -> bool {
+        self.config.detect_side_effect_renders
+            && !block.stmts.iter().any(|stmt| matches!(stmt, Stmt::Return(_)))
+            && block
+                .stmts
+                .iter()
+                .any(|stmt| matches!(stmt, Stmt::Expr(ExprStmt { expr, .. }) if self.has_vnode_call(expr)))
+    }
+
+    /// Check if a statement is a `yield` of a VNode
+    fn stmt_yields_vnode(&self, stmt: &Stmt) -> bool {
+        matches!(stmt, Stmt::Expr(ExprStmt { expr, .. })
+            if matches!(expr.unwrap_parens(), Expr::Yield(_)) && self.has_vnode_call(expr))
+    }
+
+    /// Check if a statement directly returns a VNode (not a render arrow), descending into
+    /// if/else, block and try statements to support early-return patterns.
+    /// `body` is the enclosing function body, used to resolve returned locals.
+    fn stmt_returns_vnode(&self, stmt: &Stmt, body: &BlockStmt) -> bool {
+        match stmt {
+            Stmt::Return(ret_stmt) => ret_stmt
+                .arg
+                .as_ref()
+                .is_some_and(|ret_arg| self.returns_vnode(ret_arg, body)),
+            // Guard clauses: if (loading) return createTextVNode("...");
+            Stmt::If(if_stmt) => {
+                self.stmt_returns_vnode(&if_stmt.cons, body)
+                    || if_stmt
+                        .alt
+                        .as_ref()
+                        .is_some_and(|alt| self.stmt_returns_vnode(alt, body))
+            }
+            Stmt::Block(block) => self.stmts_return_vnode(&block.stmts, body),
+            Stmt::Try(try_stmt) => {
+                self.stmts_return_vnode(&try_stmt.block.stmts, body)
+                    || try_stmt
+                        .handler
+                        .as_ref()
+                        .is_some_and(|handler| self.stmts_return_vnode(&handler.body.stmts, body))
+                    || try_stmt
+                        .finalizer
+                        .as_ref()
+                        .is_some_and(|finalizer| self.stmts_return_vnode(&finalizer.stmts, body))
+            }
+            _ => false,
+        }
+    }
+
+    fn stmts_return_vnode(&self, stmts: &[Stmt], body: &BlockStmt) -> bool {
+        stmts.iter().any(|stmt| self.stmt_returns_vnode(stmt, body))
+    }
+
+    /// Check if a returned expression is a VNode rather than a render arrow
+    fn returns_vnode(&self, ret_arg: &Expr, body: &BlockStmt) -> bool {
+        // Check if directly returning VNode (not a render arrow, wrapped or not)
+        if self.has_vnode_call(ret_arg) && !matches!(ret_arg, Expr::Arrow(_)) && !self.returns_render_arrow(ret_arg) {
+            return true;
+        }
+        // Returning a local: const tree = createVNode(...); return tree;
+        matches!(ret_arg, Expr::Ident(ident) if self.local_has_vnode_init(body, ident))
+    }
+
+    /// Check that every reachable return in `stmts` produces a VNode or nothing, for
+    /// `strictReturns`. Statements after a `return` are dead and skipped. Sets `found`
+    /// once a reachable return produces a VNode.
+    fn reachable_returns_are_vnodes(&self, stmts: &[Stmt], body: &BlockStmt, found: &mut bool) -> bool {
+        for stmt in stmts {
+            let ok = match stmt {
+                Stmt::Return(ret_stmt) => {
+                    return match &ret_stmt.arg {
+                        Some(ret_arg) if self.returns_vnode(ret_arg, body) => {
+                            *found = true;
+                            true
+                        }
+                        Some(ret_arg) => is_empty_value(ret_arg),
+                        None => true,
+                    };
+                }
+                Stmt::If(if_stmt) => {
+                    self.reachable_returns_are_vnodes(std::slice::from_ref(&*if_stmt.cons), body, found)
+                        && if_stmt.alt.as_ref().is_none_or(|alt| {
+                            self.reachable_returns_are_vnodes(std::slice::from_ref(&**alt), body, found)
+                        })
+                }
+                Stmt::Block(block) => self.reachable_returns_are_vnodes(&block.stmts, body, found),
+                Stmt::Try(try_stmt) => {
+                    self.reachable_returns_are_vnodes(&try_stmt.block.stmts, body, found)
+                        && try_stmt.handler.as_ref().is_none_or(|handler| {
+                            self.reachable_returns_are_vnodes(&handler.body.stmts, body, found)
+                        })
+                        && try_stmt.finalizer.as_ref().is_none_or(|finalizer| {
+                            self.reachable_returns_are_vnodes(&finalizer.stmts, body, found)
+                        })
+                }
+                Stmt::Switch(switch_stmt) => switch_stmt
+                    .cases
+                    .iter()
+                    .all(|case| self.reachable_returns_are_vnodes(&case.cons, body, found)),
+                Stmt::For(ForStmt { body: loop_body, .. })
+                | Stmt::ForIn(ForInStmt { body: loop_body, .. })
+                | Stmt::ForOf(ForOfStmt { body: loop_body, .. })
+                | Stmt::While(WhileStmt { body: loop_body, .. })
+                | Stmt::DoWhile(DoWhileStmt { body: loop_body, .. })
+                | Stmt::Labeled(LabeledStmt { body: loop_body, .. }) => {
+                    self.reachable_returns_are_vnodes(std::slice::from_ref(&**loop_body), body, found)
+                }
+                _ => true,
+            };
+            if !ok {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Check if a `const`/`let` declared directly in `body` binds `ident` to a VNode
+    /// expression (not an arrow function). Only the top level of the body is searched.
+    fn local_has_vnode_init(&self, body: &BlockStmt, ident: &Ident) -> bool {
+        body.stmts.iter().any(|stmt| match stmt {
+            Stmt::Decl(Decl::Var(var_decl)) if var_decl.kind != VarDeclKind::Var => {
+                var_decl.decls.iter().any(|decl| match (&decl.name, &decl.init) {
+                    (Pat::Ident(binding), Some(init)) => {
+                        binding.id.to_id() == ident.to_id()
+                            && !matches!(&**init, Expr::Arrow(_))
+                            && self.has_vnode_call(init)
+                    }
+                    _ => false,
+                })
+            }
+            _ => false,
+        })
+    }
+
+    /// Check if a function body returns an arrow function with VNode calls (stateful component)
+    fn is_rask_component(&self, func: &Function) -> bool {
+        func.body
+            .as_ref()
+            .is_some_and(|body| self.stmts_return_render_arrow(&body.stmts))
+    }
+
+    /// Check if any of the statements returns a render arrow, looking into try blocks
+    fn stmts_return_render_arrow(&self, stmts: &[Stmt]) -> bool {
+        stmts.iter().any(|stmt| match stmt {
+            Stmt::Return(ReturnStmt { arg: Some(ret_arg), .. }) => self.returns_render_arrow(ret_arg),
+            // Setup wrapped in error handling: try { ...; return () => <div /> } catch {}
+            Stmt::Try(try_stmt) => {
+                self.stmts_return_render_arrow(&try_stmt.block.stmts)
+                    || try_stmt
+                        .handler
+                        .as_ref()
+                        .is_some_and(|handler| self.stmts_return_render_arrow(&handler.body.stmts))
+                    || try_stmt
+                        .finalizer
+                        .as_ref()
+                        .is_some_and(|finalizer| self.stmts_return_render_arrow(&finalizer.stmts))
+            }
+            _ => false,
+        })
+    }
+
+    /// Check if a returned expression is a render arrow, either directly or passed
+    /// through a wrapper function: `withHooks(() => <div />)`. Method calls such as
+    /// `items.map(() => <li />)` and VNode factories produce VNodes, not render functions.
+    fn returns_render_arrow(&self, ret_arg: &Expr) -> bool {
+        match ret_arg {
+            Expr::Arrow(arrow) => self.is_render_arrow(arrow),
+            Expr::Call(CallExpr {
+                callee: Callee::Expr(callee),
+                args,
+                ..
+            }) => {
+                matches!(&**callee, Expr::Ident(ident) if !self.is_vnode_factory(ident))
+                    && args
+                        .iter()
+                        .any(|arg| matches!(&*arg.expr, Expr::Arrow(arrow) if self.is_render_arrow(arrow)))
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if an arrow's body produces VNodes, i.e. it's a render function
+    fn is_render_arrow(&self, arrow: &ArrowExpr) -> bool {
+        match &*arrow.body {
+            BlockStmtOrExpr::Expr(expr) => self.has_vnode_call(expr),
+            // Includes returns nested in if/else, switch, try, loops, ...
+            BlockStmtOrExpr::BlockStmt(block) => {
+                self.block_has_vnode_return(block) || self.renders_by_side_effect(block)
+            }
+        }
+    }
+
+    /// Recursively check if a block statement contains any return with VNode calls
+    fn block_has_vnode_return(&self, block: &BlockStmt) -> bool {
+        for stmt in &block.stmts {
+            if self.stmt_has_vnode_return(stmt) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Check if a statement (or nested statements) contains a return with VNode calls
+    fn stmt_has_vnode_return(&self, stmt: &Stmt) -> bool {
+        match stmt {
+            // Direct return statement
+            Stmt::Return(ret) => {
+                if let Some(arg) = &ret.arg {
+                    return self.has_vnode_call(arg);
+                }
+            }
+            // If statement - check both branches
+            Stmt::If(if_stmt) => {
+                // Check consequent block
+                if self.stmt_has_vnode_return(&if_stmt.cons) {
+                    return true;
+                }
+                // Check alternate (else/else if) branch
+                if let Some(alt) = &if_stmt.alt {
+                    if self.stmt_has_vnode_return(alt) {
+                        return true;
+                    }
+                }
+            }
+            // Block statement - recursively check all statements
+            Stmt::Block(block) => {
+                return self.block_has_vnode_return(block);
+            }
+            // Switch statement - check all cases
+            Stmt::Switch(switch) => {
+                for case in &switch.cases {
+                    for cons_stmt in &case.cons {
+                        if self.stmt_has_vnode_return(cons_stmt) {
+                            return true;
+                        }
+                    }
+                }
+            }
+            // Try-catch-finally - check all blocks
+            Stmt::Try(try_stmt) => {
+                if self.block_has_vnode_return(&try_stmt.block) {
+                    return true;
+                }
+                if let Some(handler) = &try_stmt.handler {
+                    if self.block_has_vnode_return(&handler.body) {
+                        return true;
+                    }
+                }
+                if let Some(finalizer) = &try_stmt.finalizer {
+                    if self.block_has_vnode_return(finalizer) {
+                        return true;
+                    }
+                }
+            }
+            // For/while/do-while loops - check body
+            Stmt::For(for_stmt) => {
+                return self.stmt_has_vnode_return(&for_stmt.body);
+            }
+            Stmt::ForIn(for_in) => {
+                return self.stmt_has_vnode_return(&for_in.body);
+            }
+            Stmt::ForOf(for_of) => {
+                return self.stmt_has_vnode_return(&for_of.body);
+            }
+            Stmt::While(while_stmt) => {
+                return self.stmt_has_vnode_return(&while_stmt.body);
+            }
+            Stmt::DoWhile(do_while) => {
+                return self.stmt_has_vnode_return(&do_while.body);
+            }
+            // Labeled statement - check the nested statement
+            Stmt::Labeled(labeled) => {
+                return self.stmt_has_vnode_return(&labeled.body);
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Classify a function as a stateful or stateless component, if it is one at all.
+    ///
+    /// Stateful detection takes precedence when a function matches both heuristics.
+    fn classify_component(&self, func: &Function) -> Option<ComponentKind> {
+        let is_stateful = self.is_rask_component(func);
+        let is_stateless = self.is_stateless_component(func);
+
+        if is_stateful && is_stateless {
+            self.ambiguous_count.set(self.ambiguous_count.get() + 1);
+            if self.config.warn_on_ambiguous {
+                emit_warning(
+                    func.span,
+                    "rask-ui: function returns both VNodes and a render arrow, treating it as a stateful component",
+                );
+            }
+        }
+
+        if is_stateful {
+            Some(ComponentKind::Stateful)
+        } else if is_stateless {
+            Some(ComponentKind::Stateless)
+        } else {
+            None
+        }
+    }
+
+    /// Resolve the component kind forced by a `@rask-stateful`/`@rask-stateless` directive
+    fn directive_kind(&self, span: Span) -> Option<ComponentKind> {
+        if self.has_directive(span, STATEFUL_DIRECTIVE) {
+            Some(ComponentKind::Stateful)
+        } else if self.has_directive(span, STATELESS_DIRECTIVE) {
+            Some(ComponentKind::Stateless)
+        } else {
+            None
+        }
+    }
+
+    /// Classify a declared function, honoring directives and `forceExports`.
+    ///
+    /// Declarations annotated with `@rask-ignore` are never components, while
+    /// `@rask-stateful`/`@rask-stateless` override the heuristics. When forced, an
+    /// exported function whose name matches the configured pattern is treated as a
+    /// component even if no VNode calls are found: stateful when it returns an arrow,
+    /// stateless otherwise.
+    fn component_kind(&self, name: &Ident, func: &Function, ctx: DeclContext) -> Option<ComponentKind> {
+        let kind = self.detect_component_kind(name, func, ctx)?;
+        self.transform_kind(ctx.span, Some(&name.sym), kind)
+    }
+
+    fn detect_component_kind(&self, name: &Ident, func: &Function, ctx: DeclContext) -> Option<ComponentKind> {
+        if self.has_directive(ctx.span, IGNORE_DIRECTIVE) || self.kept_originals.contains(&name.to_id()) {
+            return None;
+        }
+
+        if let Some(kind) = self.directive_kind(ctx.span) {
+            return Some(kind);
+        }
+
+        // `explicitOnly` transforms nothing but the annotated declarations
+        if self.config.explicit_only {
+            return None;
+        }
+
+        // Helpers that happen to build VNodes (`renderItem`) are skipped by name before
+        // running the heuristics
+        if !self.component_name_regex.is_match(&name.sym) {
+            return None;
+        }
+
+        if let Some(kind) = self.classify_component(func) {
+            return Some(kind);
+        }
+
+        let exported = ctx.exported || self.exported_names.contains(&name.sym);
+        if !exported || !self.config.force_exports || !self.force_exports_regex.is_match(&name.sym) {
+            return None;
+        }
+
+        let body = func.body.as_ref()?;
+        let returns_arrow = body.stmts.iter().any(|stmt| {
+            matches!(stmt, Stmt::Return(ReturnStmt { arg: Some(arg), .. }) if matches!(&**arg, Expr::Arrow(_)))
+        });
+
+        if returns_arrow {
+            Some(ComponentKind::Stateful)
+        } else {
+            Some(ComponentKind::Stateless)
+        }
+    }
+
+    /// Classify a function without a name, honoring directives. There is no name to match
+    /// `componentNamePattern` against, nor to force the export by.
+    fn anonymous_component_kind(&self, span: Span, func: &Function) -> Option<ComponentKind> {
+        if self.has_directive(span, IGNORE_DIRECTIVE) {
+            return None;
+        }
+        let kind = match self.directive_kind(span) {
+            Some(kind) => kind,
+            None if self.config.explicit_only => return None,
+            None => self.classify_component(func)?,
+        };
+        self.transform_kind(span, None, kind)
+    }
+
+    /// Decide whether a detected component should be transformed. In `reportOnly` mode the
+    /// component is reported as a note instead and left untouched.
+    /// `name` is `None` for anonymous default exports.
+    fn transform_kind(&self, span: Span, name: Option<&str>, kind: ComponentKind) -> Option<ComponentKind> {
+        if self.config.report_only {
+            let subject = match name {
+                Some(name) => format!("`{}`", name),
+                None => "the default export".to_string(),
+            };
+            emit_note(
+                span,
+                &format!("rask-ui: {} would become a {} component", subject, kind.label()),
+            );
+            return None;
+        }
+        Some(kind)
+    }
+
+    /// Transform a function to the component class matching its kind.
+    ///
+    /// `span` is the span of the original declaration; it is carried onto the class so
+    /// leading comments (e.g. JSDoc) attached to the declaration are still emitted.
+    ///
+    /// When `stripPrefix`/`stripSuffix` rename the class, the original binding is kept:
+    /// `const AppComponent = class App extends ... {}`.
+    ///
+    /// With `statelessMode: "function"`, stateless components stay function declarations
+    /// and are tagged after the statement instead.
+    fn transform_to_class(&mut self, span: Span, name: Ident, mut func: Function, kind: ComponentKind) -> Decl {
+        if self.keeps_function(kind) {
+            self.wrap_array_returns(&mut func);
+            self.tag_stateless_function(name.sym.clone(), Expr::Ident(name.clone()));
+            return Decl::Fn(FnDecl {
+                ident: name,
+                declare: false,
+                function: Box::new(Function { span, ..func }),
+            });
+        }
+
+        self.generated_classes.insert(name.to_id());
+
+        if let Some(class_name) = self.class_name(&name) {
+            let display_name = name.sym.clone();
+            let class_expr = ClassExpr {
+                ident: Some(class_name.clone()),
+                class: self.create_component_class(func.span, class_name, display_name, func, kind),
+            };
+            return Decl::Var(Box::new(VarDecl {
+                span,
+                ctxt: Default::default(),
+                kind: VarDeclKind::Const,
+                declare: false,
+                decls: vec![VarDeclarator {
+                    span: Default::default(),
+                    name: Pat::Ident(name.into()),
+                    init: Some(Box::new(Expr::Class(class_expr))),
+                    definite: false,
+                }],
+            }));
+        }
+
+        match kind {
+            ComponentKind::Stateful => self.transform_to_stateful_class(span, name, func),
+            ComponentKind::Stateless => self.transform_to_stateless_class(span, name, func),
+        }
+    }
+
+    /// Transform a function declaration into a component class declaration, if it is a component
+    ///
+    /// The function is moved out of `fn_decl`, which the caller replaces with the class.
+    fn transform_fn_decl(&mut self, fn_decl: &mut FnDecl, ctx: DeclContext) -> Option<Decl> {
+        let kind = self.component_kind(&fn_decl.ident, &fn_decl.function, ctx)?;
+        let name = fn_decl.ident.clone();
+        let func = std::mem::take(&mut *fn_decl.function);
+        Some(self.transform_to_class(func.span, name, func, kind))
+    }
+
+    /// Get the local identifier of the base class for a component kind, creating it
+    /// (and so requesting its import) on first use
+    fn base_class_ident(&mut self, kind: ComponentKind) -> Ident {
+        let imported = match kind {
+            ComponentKind::Stateful => self.config.stateful_base_class().to_string(),
+            ComponentKind::Stateless => self.config.stateless_base_class().to_string(),
+        };
+        self.runtime_import(imported)
+    }
+
+    /// Get the local identifier of a named import from the configured source, requesting
+    /// the import on first use. With `minifyImports` it is bound to a short alias
+    /// (`RaskStatefulComponent as _a`) instead of its own name.
+    fn runtime_import(&mut self, imported: String) -> Ident {
+        if let Some((_, local)) = self.pending_imports.iter().find(|(name, _)| *name == imported) {
+            return local.clone();
+        }
+        let local = if self.config.minify_imports {
+            // Skip aliases the module already declares, so hygiene doesn't rename its bindings
+            let alias = (self.pending_imports.len()..)
+                .map(|index| Atom::from(short_alias(index)))
+                .find(|alias| !self.used_names.contains(alias))
+                .unwrap();
+            self.used_names.insert(alias.clone());
+            private_ident!(alias)
+        } else {
+            private_ident!(imported.as_str())
+        };
+        self.pending_imports.push((imported, local.clone()));
+        local
+    }
+
+    /// Reference the base class for a component kind: its local binding, or with
+    /// `importStyle: "namespace"` a member of the runtime namespace (`Rask.RaskStatefulComponent`)
+    fn base_class_expr(&mut self, kind: ComponentKind) -> Expr {
+        if self.config.namespace_imports() {
+            let name = match kind {
+                ComponentKind::Stateful => Atom::from(self.config.stateful_base_class()),
+                ComponentKind::Stateless => Atom::from(self.config.stateless_base_class()),
+            };
+            return self.runtime_namespace_member(name);
+        }
+        Expr::Ident(self.base_class_ident(kind))
+    }
+
+    /// Reference an export of the configured source: its imported binding, or with
+    /// `importStyle: "namespace"` a member of the runtime namespace
+    fn runtime_expr(&mut self, imported: String) -> Expr {
+        if self.config.namespace_imports() {
+            return self.runtime_namespace_member(Atom::from(imported));
+        }
+        Expr::Ident(self.runtime_import(imported))
+    }
+
+    /// Wrap arrays a stateless component returns directly in `createFragment(children, 0)`,
+    /// for `autoFragment`. Nested functions are left alone.
+    fn wrap_array_returns(&mut self, func: &mut Function) {
+        if !self.config.auto_fragment {
+            return;
+        }
+        let mut wrapper = FragmentWrapper {
+            factory: None,
+            found: false,
+        };
+        func.body.visit_mut_with(&mut wrapper);
+        if wrapper.found {
+            wrapper.factory = Some(self.runtime_expr("createFragment".to_string()));
+            func.body.visit_mut_with(&mut wrapper);
+        }
+    }
+
+    /// Create `Rask.name`, requesting the namespace import on first use
+    fn runtime_namespace_member(&mut self, name: Atom) -> Expr {
+        let namespace = self
+            .import_rask_namespace
+            .get_or_insert_with(|| private_ident!("Rask"))
+            .clone();
+        Expr::Member(MemberExpr {
+            span: Default::default(),
+            obj: Box::new(Expr::Ident(namespace)),
+            prop: MemberProp::Ident(IdentName::new(name, DUMMY_SP)),
+        })
+    }
+
+    /// Create the class member holding the component function.
+    ///
+    /// By default this is a field: `setup = function name() { ... }`. With `useMethods`
+    /// it is a method instead: `setup() { ... }`, keeping the async/generator flags, unless
+    /// the function calls itself.
+    /// With `memoStateless`, a stateless field is wrapped: `renderFn = memoize(function name() { ... })`,
+    /// also under `useMethods`.
+    /// With `keepOriginal`, the function stays a declaration renamed to `nameImpl` before the
+    /// class, which the field refers to: `setup = AppImpl`. It is exported along with an
+    /// exported component.
+    fn create_component_member(&mut self, name: Ident, func: Function, kind: ComponentKind) -> ClassMember {
+        let key = quote_ident!(match kind {
+            ComponentKind::Stateful => self.config.setup_property(),
+            ComponentKind::Stateless => self.config.render_property(),
+        });
+
+        // Members keep the function's span so source maps point setup code at the original
+        let span = func.span;
+
+        if self.config.keep_original {
+            let impl_name = self.fresh_component_name(Some(&format!("{}Impl", name.sym)));
+            self.kept_originals.insert(impl_name.to_id());
+            self.hoisted_stmts.push(Stmt::Decl(Decl::Fn(FnDecl {
+                ident: impl_name.clone(),
+                declare: false,
+                function: Box::new(func),
+            })));
+            let value = self.memo_stateless_value(kind, Expr::Ident(impl_name));
+            return Self::component_prop(span, key, value);
+        }
+
+        // A method has no binding of its own name, so recursive components stay fields:
+        // `Tree(child)` would call the class otherwise
+        let recursive = IdentUsageFinder::find(&name, &func.body);
+        // A method can't be wrapped, so memoized render functions stay fields
+        let memoized = kind == ComponentKind::Stateless && self.config.memo_helper().is_some();
+        if self.config.use_methods && !recursive && !memoized {
+            return ClassMember::Method(ClassMethod {
+                span,
+                key: PropName::Ident(key),
+                function: Box::new(func),
+                kind: MethodKind::Method,
+                is_static: false,
+                accessibility: None,
+                is_abstract: false,
+                is_optional: false,
+                is_override: false,
+            });
+        }
+
+        let value = Expr::Fn(FnExpr {
+            ident: Some(name),
+            function: Box::new(func),
+        });
+        let value = self.memo_stateless_value(kind, value);
+        Self::component_prop(span, key, value)
+    }
+
+    /// Wrap a stateless component function in the `memoStateless` helper when enabled
+    fn memo_stateless_value(&mut self, kind: ComponentKind, value: Expr) -> Expr {
+        let helper = match self.config.memo_helper() {
+            Some(helper) if kind == ComponentKind::Stateless => helper.to_string(),
+            _ => return value,
+        };
+        Expr::Call(CallExpr {
+            span: Default::default(),
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(self.runtime_expr(helper))),
+            args: vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(value),
+            }],
+            type_args: None,
+        })
+    }
+
+    /// Create the `setup`/`renderFn` field holding the component function
+    fn component_prop(span: Span, key: IdentName, value: Expr) -> ClassMember {
+        ClassMember::ClassProp(ClassProp {
+            span,
+            key: PropName::Ident(key),
+            value: Some(Box::new(value)),
+            type_ann: None,
+            is_static: false,
+            decorators: vec![],
+            accessibility: None,
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+            readonly: false,
+            declare: false,
+            definite: false,
+        })
+    }
+
+    /// Create the component class extending the base class for `kind`.
+    ///
+    /// `display_name` is the component's original name, which `emitDisplayName` records in
+    /// a `static displayName` member for devtools even when the class is renamed.
+    fn create_component_class(
+        &mut self,
+        span: Span,
+        name: Ident,
+        display_name: Atom,
+        func: Function,
+        kind: ComponentKind,
+    ) -> Box<Class> {
+        self.count_component(kind, display_name.clone());
+
+        let mut func = func;
+        if kind == ComponentKind::Stateless {
+            self.wrap_array_returns(&mut func);
+        }
+        let render_method = match kind {
+            ComponentKind::Stateful if self.config.render_from_setup => self.split_render_method(&mut func),
+            _ => None,
+        };
+
+        let super_class = self.base_class_expr(kind);
+        let mut body = vec![];
+        if self.config.emit_constructor {
+            body.push(super_constructor());
+        }
+        body.push(self.create_component_member(name, func, kind));
+        body.extend(render_method);
+        if self.config.emit_display_name {
+            body.push(display_name_member(display_name));
+        }
+
+        Box::new(Class {
+            span,
+            ctxt: Default::default(),
+            decorators: vec![],
+            body,
+            super_class: Some(Box::new(super_class)),
+            is_abstract: false,
+            type_params: None,
+            super_type_params: None,
+            implements: vec![],
+        })
+    }
+
+    /// Lift the render arrow returned at the end of a setup function into its own class
+    /// method, for `renderFromSetup`.
+    ///
+    /// Setup bindings the arrow reads are passed to the method on every render, so setup
+    /// ends with `return () => this.renderView(count, items)`. The function is left as is
+    /// when the arrow takes parameters, is async, or assigns to one of those bindings.
+    fn split_render_method(&self, func: &mut Function) -> Option<ClassMember> {
+        let body = func.body.as_mut()?;
+        let Some(Stmt::Return(ReturnStmt { arg: Some(arg), .. })) = body.stmts.last() else {
+            return None;
+        };
+        let Expr::Arrow(arrow) = &**arg else {
+            return None;
+        };
+        if arrow.is_async || arrow.is_generator || !arrow.params.is_empty() {
+            return None;
+        }
+
+        // Parameters and declarations of setup that the render arrow reads, including a `var`
+        // nested in a block of setup
+        let mut locals: Vec<Ident> = find_pat_ids(&func.params);
+        let mut bindings = BodyBindingFinder::default();
+        for stmt in &body.stmts[..body.stmts.len() - 1] {
+            stmt.visit_with(&mut bindings);
+        }
+        locals.extend(bindings.found);
+        let mut captured: Vec<Ident> = vec![];
+        for local in locals {
+            let seen = captured.iter().any(|ident| ident.to_id() == local.to_id());
+            if !seen && IdentUsageFinder::find(&local, &*arrow.body) {
+                captured.push(local);
+            }
+        }
+
+        let mut assignments = AssignmentFinder {
+            bindings: &captured,
+            found: false,
+        };
+        arrow.body.visit_with(&mut assignments);
+        if assignments.found {
+            return None;
+        }
+
+        let Some(Stmt::Return(ReturnStmt { span: return_span, arg: Some(arg) })) = body.stmts.last_mut() else {
+            return None;
+        };
+        let Expr::Arrow(arrow) = &mut **arg else {
+            return None;
+        };
+
+        // Comments above the returned arrow describe the render code, so they move to the
+        // method. The arrow left in setup loses its span so they aren't printed twice.
+        let method_span = arrow.span;
+        if let Some(comments) = &self.comments {
+            let mut leading = comments.take_leading(return_span.lo).unwrap_or_default();
+            leading.extend(comments.take_leading(method_span.lo).unwrap_or_default());
+            if !leading.is_empty() {
+                comments.add_leading_comments(method_span.lo, leading);
+            }
+        }
+        arrow.span = DUMMY_SP;
+
+        let render_body = std::mem::replace(
+            &mut *arrow.body,
+            BlockStmtOrExpr::Expr(Box::new(Expr::Call(CallExpr {
+                span: Default::default(),
+                ctxt: Default::default(),
+                callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                    span: Default::default(),
+                    obj: Box::new(Expr::This(ThisExpr {
+                        span: Default::default(),
+                    })),
+                    prop: MemberProp::Ident(quote_ident!(SETUP_RENDER_METHOD)),
+                }))),
+                args: captured
+                    .iter()
+                    .map(|ident| ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(Expr::Ident(ident.clone())),
+                    })
+                    .collect(),
+                type_args: None,
+            }))),
+        );
+
+        let render_block = match render_body {
+            BlockStmtOrExpr::BlockStmt(block) => block,
+            BlockStmtOrExpr::Expr(expr) => BlockStmt {
+                span: Default::default(),
+                ctxt: Default::default(),
+                stmts: vec![Stmt::Return(ReturnStmt {
+                    span: Default::default(),
+                    arg: Some(expr),
+                })],
+            },
+        };
+
+        Some(ClassMember::Method(ClassMethod {
+            span: method_span,
+            key: PropName::Ident(quote_ident!(SETUP_RENDER_METHOD)),
+            function: Box::new(Function {
+                params: captured
+                    .into_iter()
+                    .map(|ident| Param {
+                        span: Default::default(),
+                        decorators: vec![],
+                        pat: Pat::Ident(ident.into()),
+                    })
+                    .collect(),
+                decorators: vec![],
+                span: method_span,
+                ctxt: Default::default(),
+                body: Some(render_block),
+                is_generator: false,
+                is_async: false,
+                type_params: None,
+                return_type: arrow.return_type.take(),
+            }),
+            kind: MethodKind::Method,
+            is_static: false,
+            accessibility: None,
+            is_abstract: false,
+            is_optional: false,
+            is_override: false,
+        }))
+    }
+
+    /// Transform a function declaration to a stateful component class
+    fn transform_to_stateful_class(&mut self, span: Span, name: Ident, func: Function) -> Decl {
+        Decl::Class(ClassDecl {
+            ident: name.clone(),
+            declare: false,
+            class: self.create_component_class(span, name.clone(), name.sym, func, ComponentKind::Stateful),
+        })
+    }
+
+    /// Transform a function declaration to a stateless component class
+    fn transform_to_stateless_class(&mut self, span: Span, name: Ident, func: Function) -> Decl {
+        Decl::Class(ClassDecl {
+            ident: name.clone(),
+            declare: false,
+            class: self.create_component_class(span, name.clone(), name.sym, func, ComponentKind::Stateless),
+        })
+    }
+
+    /// Check if a component of this kind is kept as a function (`statelessMode: "function"`)
+    fn keeps_function(&self, kind: ComponentKind) -> bool {
+        kind == ComponentKind::Stateless && self.config.stateless_as_function()
+    }
+
+    /// Queue `target.rask = true` to run after the current statement, marking a stateless
+    /// component kept as a function for the runtime
+    fn tag_stateless_function(&mut self, name: Atom, target: Expr) {
+        self.count_component(ComponentKind::Stateless, name);
+        self.tagged_functions.push(target);
+    }
+
+    /// Take the queued `statelessMode: "function"` tags as assignment statements
+    fn take_tag_stmts(&mut self) -> Vec<Stmt> {
+        std::mem::take(&mut self.tagged_functions)
+            .into_iter()
+            .map(|target| {
+                Stmt::Expr(ExprStmt {
+                    span: Default::default(),
+                    expr: Box::new(Expr::Assign(AssignExpr {
+                        span: Default::default(),
+                        op: AssignOp::Assign,
+                        left: AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+                            span: Default::default(),
+                            obj: Box::new(target),
+                            prop: MemberProp::Ident(quote_ident!(STATELESS_TAG_PROPERTY)),
+                        })),
+                        right: Box::new(Expr::Lit(Lit::Bool(Bool {
+                            span: Default::default(),
+                            value: true,
+                        }))),
+                    })),
+                })
+            })
+            .collect()
+    }
+
+    /// Create the expression a component initializer is replaced with: a class expression,
+    /// or with `statelessMode: "function"` a named function expression for stateless
+    /// components. `tag_target` is the expression the function is reachable through.
+    fn create_component_expr(&mut self, name: Ident, mut func: Function, kind: ComponentKind, tag_target: Expr) -> Expr {
+        if self.keeps_function(kind) {
+            self.wrap_array_returns(&mut func);
+            self.tag_stateless_function(name.sym.clone(), tag_target);
+            return Expr::Fn(FnExpr {
+                ident: Some(name),
+                function: Box::new(func),
+            });
+        }
+        Expr::Class(self.create_component_class_expr(name, func, kind))
+    }
+
+    /// Create a class expression for variable assignments
+    fn create_component_class_expr(&mut self, name: Ident, func: Function, kind: ComponentKind) -> ClassExpr {
+        let display_name = name.sym.clone();
+        let name = self.class_name(&name).unwrap_or(name);
+        ClassExpr {
+            ident: Some(name.clone()),
+            class: self.create_component_class(func.span, name, display_name, func, kind),
+        }
+    }
+
+    /// Name for a component class renamed by `stripPrefix`/`stripSuffix`, if any. The name
+    /// is left unchanged when the stripped name is already bound in the module, since the
+    /// class name would shadow that binding inside the component.
+    fn class_name(&self, name: &Ident) -> Option<Ident> {
+        let stripped = Atom::from(self.config.strip_affixes(&name.sym)?);
+        if self.used_names.contains(&stripped) {
+            return None;
+        }
+        Some(Ident::new(stripped, name.span, name.ctxt))
+    }
+
+    /// Convert arrow function to regular function for analysis
+    fn arrow_to_function(&self, arrow: &ArrowExpr) -> Function {
+        // Convert arrow params (Vec<Pat>) to function params (Vec<Param>)
+        let params = arrow
+            .params
+            .iter()
+            .map(|pat| Param {
+                span: Default::default(),
+                decorators: vec![],
+                pat: pat.clone(),
+            })
+            .collect();
+
+        Function {
+            params,
+            decorators: vec![],
+            span: arrow.span,
+            ctxt: Default::default(),
+            body: Some(match &*arrow.body {
+                BlockStmtOrExpr::BlockStmt(block) => block.clone(),
+                BlockStmtOrExpr::Expr(expr) => BlockStmt {
+                    span: Default::default(),
+                    ctxt: Default::default(),
+                    stmts: vec![Stmt::Return(ReturnStmt {
+                        span: Default::default(),
+                        arg: Some(expr.clone()),
+                    })],
+                },
+            }),
+            is_generator: arrow.is_generator,
+            is_async: arrow.is_async,
+            type_params: arrow.type_params.clone(),
+            return_type: arrow.return_type.clone(),
+        }
+    }
+
+    /// View a variable initializer (arrow or function expression) as a function for analysis.
+    /// Function expressions are borrowed, arrows are converted to a new function.
+    fn init_to_function<'a>(&self, init: &'a Expr) -> Option<Cow<'a, Function>> {
+        // Parenthesized initializers: const App = (() => <div />)
+        match init.unwrap_parens() {
+            Expr::Arrow(arrow) => Some(Cow::Owned(self.arrow_to_function(arrow))),
+            Expr::Fn(fn_expr) => Some(Cow::Borrowed(&fn_expr.function)),
+            _ => None,
+        }
+    }
+
+    /// Classify an initializer with `classify`, then move the component function out of it.
+    ///
+    /// Function expressions are taken rather than cloned, and arrows reuse the function
+    /// converted for analysis. The caller must replace `init` when this returns `Some`.
+    fn take_component_init(
+        &self,
+        init: &mut Expr,
+        classify: impl FnOnce(&Function) -> Option<ComponentKind>,
+    ) -> Option<(ComponentKind, Function)> {
+        let (kind, converted) = {
+            let func = self.init_to_function(init)?;
+            let kind = classify(&func)?;
+            match func {
+                Cow::Owned(func) => (kind, Some(func)),
+                Cow::Borrowed(_) => (kind, None),
+            }
+        };
+        let func = match (converted, init.unwrap_parens_mut()) {
+            (Some(func), Expr::Arrow(arrow)) => {
+                warn_on_arrow_this(arrow);
+                func
+            }
+            (Some(func), _) => func,
+            (None, Expr::Fn(fn_expr)) => std::mem::take(&mut *fn_expr.function),
+            // Only function expressions are borrowed for analysis
+            (None, _) => return None,
+        };
+        Some((kind, func))
+    }
+
+    /// Transform component declarators in a variable declaration.
+    ///
+    /// A `const`/`let` statement holding a single component is turned into a class
+    /// declaration, which is returned so the caller can replace the statement. When the
+    /// statement has several declarators, only the matching initializers are rewritten
+    /// in place to class expressions.
+    fn transform_var_decl(&mut self, var_decl: &mut VarDecl, ctx: DeclContext) -> Option<Decl> {
+        if var_decl.decls.len() == 1 && var_decl.kind != VarDeclKind::Var {
+            let decl = &mut var_decl.decls[0];
+            if let (Pat::Ident(ident_pat), Some(init)) = (&decl.name, &mut decl.init) {
+                // A differently named function expression, `const App = function Inner() {}`,
+                // is left to the in-place rewrite below so `Inner` stays bound
+                let same_name = fn_expr_ident(init).is_none_or(|fn_name| fn_name.sym == ident_pat.id.sym);
+                if ident_pat.type_ann.is_none() && same_name {
+                    let classify = |func: &Function| self.component_kind(&ident_pat.id, func, ctx);
+                    if let Some((kind, func)) = self.take_component_init(init, classify) {
+                        let name = ident_pat.id.clone();
+                        return Some(self.transform_to_class(var_decl.span, name, func, kind));
+                    }
+                }
+            }
+        }
+
+        for decl in &mut var_decl.decls {
+            if let (Pat::Ident(ident_pat), Some(init)) = (&decl.name, &mut decl.init) {
+                // Handle: const App = class { render() { return <div /> } }
+                if let Expr::Class(class_expr) = &mut **init {
+                    self.rebase_class_component(&ident_pat.id, &mut class_expr.class, ctx);
+                    continue;
+                }
+
+                // Handle: const App = observer(() => () => <div />)
+                if self.config.unwrap_hocs && self.unwrap_hoc_component(&ident_pat.id, init, ctx) {
+                    continue;
+                }
+
+                // Handle: const components = { App: () => <div />, Page() { return () => <div /> } }
+                if let Expr::Object(obj) = &mut **init {
+                    self.transform_object_registry(&ident_pat.id, obj);
+                    continue;
+                }
+
+                // Function expressions keep their own name: `const App = class Inner extends ...`
+                let fn_name = fn_expr_ident(init);
+                let classify = |func: &Function| self.component_kind(&ident_pat.id, func, ctx);
+                if let Some((kind, func)) = self.take_component_init(init, classify) {
+                    let binding = ident_pat.id.clone();
+                    if !self.keeps_function(kind) {
+                        self.generated_classes.insert(binding.to_id());
+                    }
+                    let name = fn_name.unwrap_or_else(|| binding.clone());
+                    **init = self.create_component_expr(name, func, kind, Expr::Ident(binding));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Transform a component wrapped in a single-argument HOC call, for `unwrapHocs`.
+    ///
+    /// `const App = observer(() => <div />)` becomes `class App_ extends ... {}` (queued
+    /// in `hoisted_stmts` to go before the statement) and `const App = observer(App_)`.
+    fn unwrap_hoc_component(&mut self, name: &Ident, init: &mut Expr, ctx: DeclContext) -> bool {
+        let Expr::Call(call) = init.unwrap_parens_mut() else {
+            return false;
+        };
+        if !matches!(call.callee, Callee::Expr(_)) || call.args.len() != 1 || call.args[0].spread.is_some() {
+            return false;
+        }
+
+        let classify = |func: &Function| self.component_kind(name, func, ctx);
+        let Some((kind, func)) = self.take_component_init(&mut call.args[0].expr, classify) else {
+            return false;
+        };
+
+        let class_name = self.fresh_component_name(Some(&format!("{}_", name.sym)));
+        let class_decl = self.transform_to_class(func.span, class_name.clone(), func, kind);
+        self.hoisted_stmts.push(Stmt::Decl(class_decl));
+        // Tag a stateless function before the HOC receives it
+        let tag_stmts = self.take_tag_stmts();
+        self.hoisted_stmts.extend(tag_stmts);
+        *call.args[0].expr = Expr::Ident(class_name);
+        true
+    }
+
+    /// Transform a component assigned to a CommonJS export (`module.exports = ...`,
+    /// `exports.App = ...` or `module.exports.App = ...`) into a class expression in place.
+    /// The class is named after the export, or the function's own name when it has one.
+    fn transform_commonjs_export(&mut self, assign: &mut AssignExpr, span: Span) {
+        if assign.op != AssignOp::Assign {
+            return;
+        }
+        let AssignTarget::Simple(SimpleAssignTarget::Member(target)) = &assign.left else {
+            return;
+        };
+        let Some(export_name) = commonjs_export_name(target) else {
+            return;
+        };
+
+        let ctx = DeclContext { span, exported: true };
+        let fn_name = fn_expr_ident(&assign.right);
+        let export_ident = export_name.map(|name| private_ident!(name.sym.clone()));
+        let classify_name = export_ident.clone().or_else(|| fn_name.clone());
+        let classify = |func: &Function| match &classify_name {
+            Some(name) => self.component_kind(name, func, ctx),
+            // `module.exports = () => ...` has no name, like an anonymous default export
+            None => self.anonymous_component_kind(span, func),
+        };
+        let Some((kind, func)) = self.take_component_init(&mut assign.right, classify) else {
+            return;
+        };
+
+        let name = match fn_name.or(export_ident) {
+            Some(name) => name,
+            None => self.fresh_component_name(Some("DefaultComponent")),
+        };
+        let tag_target = Expr::Member(target.clone());
+        *assign.right = self.create_component_expr(name, func, kind, tag_target);
+    }
+
+    /// Take the queued HOC component classes as statements, visiting them for nested components
+    fn take_hoisted_stmts(&mut self) -> Vec<Stmt> {
+        std::mem::take(&mut self.hoisted_stmts)
+            .into_iter()
+            .map(|mut stmt| {
+                stmt.visit_mut_children_with(self);
+                stmt
+            })
+            .collect()
+    }
+
+    /// Take the queued statements as module items. With `keepOriginal`, the kept function
+    /// of an exported component is exported too: `export function AppImpl`.
+    fn take_hoisted_items(&mut self, exported: bool) -> Vec<ModuleItem> {
+        self.take_hoisted_stmts()
+            .into_iter()
+            .map(|stmt| match stmt {
+                Stmt::Decl(Decl::Fn(fn_decl)) if exported && self.kept_originals.contains(&fn_decl.ident.to_id()) => {
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                        span: DUMMY_SP,
+                        decl: Decl::Fn(fn_decl),
+                    }))
+                }
+                stmt => ModuleItem::Stmt(stmt),
+            })
+            .collect()
+    }
+
+    /// Transform components in an object-literal registry: function, arrow and method
+    /// property values become class expressions named after their key. `registry` is the
+    /// binding holding the object, through which stateless functions are tagged.
+    fn transform_object_registry(&mut self, registry: &Ident, obj: &mut ObjectLit) {
+        for prop in &mut obj.props {
+            let PropOrSpread::Prop(prop) = prop else {
+                continue;
+            };
+            let ctx = DeclContext {
+                span: prop.span(),
+                exported: false,
+            };
+
+            match &mut **prop {
+                Prop::KeyValue(key_value) => {
+                    let name = self.registry_component_name(&key_value.key);
+                    let classify = |func: &Function| self.component_kind(&name, func, ctx);
+                    if let Some((kind, func)) = self.take_component_init(&mut key_value.value, classify) {
+                        let tag_target = registry_member(registry, &key_value.key);
+                        *key_value.value = self.create_component_expr(name, func, kind, tag_target);
+                    }
+                }
+                Prop::Method(method) => {
+                    let name = self.registry_component_name(&method.key);
+                    if let Some(kind) = self.component_kind(&name, &method.function, ctx) {
+                        let func = std::mem::take(&mut *method.function);
+                        let tag_target = registry_member(registry, &method.key);
+                        let value = self.create_component_expr(name, func, kind, tag_target);
+                        **prop = Prop::KeyValue(KeyValueProp {
+                            key: method.key.clone(),
+                            value: Box::new(value),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Name for a component class in an object registry, taken from its property key.
+    /// Keys that aren't valid identifiers get a synthesized name.
+    fn registry_component_name(&mut self, key: &PropName) -> Ident {
+        let sym = match key {
+            PropName::Ident(ident) => Some(&*ident.sym),
+            PropName::Str(str) => str.value.as_str(),
+            _ => None,
+        };
+        match sym.filter(|sym| Ident::verify_symbol(sym).is_ok()) {
+            // Private, so hygiene renames it if it would shadow a binding the component uses
+            Some(sym) => private_ident!(Atom::from(sym)),
+            None => self.fresh_component_name(None),
+        }
+    }
 
-pub struct RaskComponentTransform {
-    config: Config,
-    import_rask_stateful_component: Option<Ident>,
-    import_rask_stateless_component: Option<Ident>,
-}
+    /// Rebase a class expression component onto the stateless base class by setting its
+    /// superclass. Classes that already extend something are left alone.
+    fn rebase_class_component(&mut self, name: &Ident, class: &mut Class, ctx: DeclContext) {
+        // Output of an earlier run, e.g. when the plugin is configured twice
+        if self.is_already_rask_class(class) {
+            return;
+        }
+        if class.super_class.is_some() || self.has_directive(ctx.span, IGNORE_DIRECTIVE) {
+            return;
+        }
+        if self.config.explicit_only && !self.has_directive(ctx.span, STATELESS_DIRECTIVE) {
+            return;
+        }
 
-impl RaskComponentTransform {
-    fn new(config: Config) -> Self {
-        RaskComponentTransform {
-            config,
-            import_rask_stateful_component: None,
-            import_rask_stateless_component: None,
+        let renders_vnodes = class.body.iter().any(|member| match member {
+            ClassMember::Method(method) => {
+                method.kind == MethodKind::Method
+                    && !method.is_static
+                    && matches!(&method.key, PropName::Ident(key) if &*key.sym == "render")
+                    && self.is_stateless_component(&method.function)
+            }
+            _ => false,
+        });
+        if !renders_vnodes {
+            return;
+        }
+
+        if self
+            .transform_kind(ctx.span, Some(&name.sym), ComponentKind::Stateless)
+            .is_none()
+        {
+            return;
+        }
+
+        self.count_component(ComponentKind::Stateless, name.sym.clone());
+        class.super_class = Some(Box::new(self.base_class_expr(ComponentKind::Stateless)));
+
+        let has_constructor = class
+            .body
+            .iter()
+            .any(|member| matches!(member, ClassMember::Constructor(_)));
+        if self.config.emit_constructor && !has_constructor {
+            class.body.insert(0, super_constructor());
         }
     }
 
-    /// Check if an expression contains a VNode-related call (recursive deep search)
-    fn has_vnode_call(&self, expr: &Expr) -> bool {
-        match expr {
-            // Direct VNode call - this is what we're looking for
-            Expr::Call(call) => {
-                if let Callee::Expr(callee_expr) = &call.callee {
-                    if let Expr::Ident(ident) = &**callee_expr {
-                        if ident.sym.as_ref() == "createVNode"
-                            || ident.sym.as_ref() == "createComponentVNode"
-                            || ident.sym.as_ref() == "createFragment"
-                            || ident.sym.as_ref() == "createTextVNode"
-                        {
-                            return true;
-                        }
-                    }
+    /// Check if a class already extends one of the configured base classes, either by name,
+    /// through the local binding of an existing import or as a namespace member
+    fn is_already_rask_class(&self, class: &Class) -> bool {
+        let is_base_class = |name: &str| {
+            name == self.config.stateful_base_class() || name == self.config.stateless_base_class()
+        };
+        let super_class = match class.super_class.as_deref() {
+            Some(Expr::Ident(super_class)) => super_class,
+            Some(Expr::Member(MemberExpr {
+                prop: MemberProp::Ident(prop),
+                ..
+            })) => return is_base_class(&prop.sym),
+            _ => return false,
+        };
+        is_base_class(&super_class.sym)
+            || self
+                .pending_imports
+                .iter()
+                .any(|(imported, local)| is_base_class(imported) && local.to_id() == super_class.to_id())
+    }
+
+    /// Collect the functions the classes of an earlier run refer to with `keepOriginal`:
+    /// `setup = AppImpl`, or `renderFn = memoize(AppImpl)`. Running the transform again
+    /// must not turn them into classes of their own.
+    fn collect_kept_originals(&mut self, module: &Module) {
+        let members = |key: &str| key == self.config.setup_property() || key == self.config.render_property();
+        let mut kept = HashSet::new();
+        for class in module.body.iter().filter_map(declared_class) {
+            if !self.is_already_rask_class(class) {
+                continue;
+            }
+            for member in &class.body {
+                let ClassMember::ClassProp(ClassProp {
+                    key: PropName::Ident(key),
+                    value: Some(value),
+                    ..
+                }) = member
+                else {
+                    continue;
+                };
+                if !members(&key.sym) {
+                    continue;
                 }
-                // Check arguments - important for .map(...), .filter(...), etc.
-                for arg in &call.args {
-                    if self.has_vnode_call(&arg.expr) {
-                        return true;
-                    }
+                let value = match &**value {
+                    Expr::Call(call) if call.args.len() == 1 => &*call.args[0].expr,
+                    value => value,
+                };
+                if let Expr::Ident(original) = value {
+                    kept.insert(original.to_id());
                 }
-                false
             }
-            // Parenthesized expressions
-            Expr::Paren(paren) => self.has_vnode_call(&paren.expr),
+        }
+        self.kept_originals = kept;
+    }
 
-            // Conditional (ternary): condition ? consequent : alternate
-            Expr::Cond(cond) => {
-                self.has_vnode_call(&cond.cons) || self.has_vnode_call(&cond.alt)
+    /// Pick a name for a synthesized component class that doesn't collide with any
+    /// binding declared in the module (or previously synthesized).
+    ///
+    /// With a hint the names are `DefaultComponent`, `DefaultComponent1`, ...; without one
+    /// they are `Component0`, `Component1`, ... Names only depend on the module's contents,
+    /// so identical input always gets identical names.
+    fn fresh_component_name(&mut self, hint: Option<&str>) -> Ident {
+        let (base, mut suffix) = match hint {
+            Some(hint) => (hint, None),
+            None => ("Component", Some(0)),
+        };
+        loop {
+            let candidate = match suffix {
+                Some(suffix) => Atom::from(format!("{}{}", base, suffix)),
+                None => Atom::from(base),
+            };
+            if self.used_names.insert(candidate.clone()) {
+                return private_ident!(candidate);
             }
+            suffix = Some(suffix.map_or(1, |suffix| suffix + 1));
+        }
+    }
 
-            // Logical: expr1 && expr2, expr1 || expr2
-            Expr::Bin(bin) => {
-                self.has_vnode_call(&bin.left) || self.has_vnode_call(&bin.right)
-            }
+    /// Transform a default-exported component into a named class declaration.
+    ///
+    /// Handles both `export default function App() {}` and `export default () => {}`.
+    /// Returns the class declaration along with the identifier that should be re-exported
+    /// as default. Anonymous components get a collision-free `DefaultComponent` name.
+    ///
+    /// The component function is moved out of `decl`, which the caller replaces.
+    fn transform_default_export(&mut self, decl: &mut ModuleDecl) -> Option<(Decl, Ident)> {
+        let span = decl.span();
+        if self.has_directive(span, IGNORE_DIRECTIVE) {
+            return None;
+        }
 
-            // Arrays: [expr1, expr2, ...]
-            Expr::Array(arr) => {
-                arr.elems.iter().any(|elem| {
-                    elem.as_ref()
-                        .map(|e| self.has_vnode_call(&e.expr))
-                        .unwrap_or(false)
-                })
+        let directive_kind = self.directive_kind(span);
+        let classify = |ident: &Option<Ident>, func: &Function| {
+            // Anonymous default exports have no name to match `componentNamePattern`
+            let kind = match directive_kind {
+                Some(kind) => kind,
+                None if self.config.explicit_only => return None,
+                None if ident.as_ref().is_some_and(|ident| !self.component_name_regex.is_match(&ident.sym)) => {
+                    return None;
+                }
+                None => self.classify_component(func)?,
+            };
+            self.transform_kind(span, ident.as_ref().map(|ident| &*ident.sym), kind)
+        };
+        let (ident, kind, func) = match decl {
+            ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                decl: DefaultDecl::Fn(fn_expr),
+                ..
+            }) => {
+                let kind = classify(&fn_expr.ident, &fn_expr.function)?;
+                (fn_expr.ident.clone(), kind, std::mem::take(&mut *fn_expr.function))
+            }
+            ModuleDecl::ExportDefaultExpr(export) => {
+                let expr = export.expr.unwrap_parens_mut();
+                let ident = fn_expr_ident(expr);
+                let (kind, func) = self.take_component_init(expr, |func| classify(&ident, func))?;
+                (ident, kind, func)
             }
+            _ => return None,
+        };
 
-            // Arrow functions: (args) => body
-            Expr::Arrow(arrow) => match &*arrow.body {
-                BlockStmtOrExpr::Expr(expr) => self.has_vnode_call(expr),
-                BlockStmtOrExpr::BlockStmt(block) => {
-                    for stmt in &block.stmts {
-                        if let Stmt::Return(ret) = stmt {
-                            if let Some(arg) = &ret.arg {
-                                if self.has_vnode_call(arg) {
-                                    return true;
-                                }
-                            }
+        let name = ident.unwrap_or_else(|| self.fresh_component_name(Some("DefaultComponent")));
+        let class_decl = self.transform_to_class(span, name.clone(), func, kind);
+        Some((class_decl, name))
+    }
+
+    /// Rewrite imports and re-exports from "inferno" (or the configured `rewriteFrom` sources)
+    /// to the configured import source, and any `importMap` sources to their mapped target.
+    /// Rewritten imports and named re-exports drop the `pruneUnknownImports` names, and are
+    /// removed entirely when nothing is left.
+    fn rewrite_inferno_imports(&mut self, module: &mut Module) {
+        let import_source = self.config.import_source();
+
+        let mut body = Vec::with_capacity(module.body.len());
+
+        for mut item in module.body.drain(..) {
+            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = &mut item {
+                let source = import.src.value.to_string_lossy();
+
+                // Explicitly mapped sources, e.g. "inferno-hydrate" -> "rask-ui/hydrate"
+                if let Some(mapped) = self
+                    .config
+                    .import_map
+                    .as_ref()
+                    .and_then(|import_map| import_map.get(&*source))
+                {
+                    *import.src = Str {
+                        span: Default::default(),
+                        value: Wtf8Atom::from(mapped.as_str()),
+                        raw: None,
+                    };
+                } else if self.config.rewrites_source(&source) {
+                    // Rewrite the import source from "inferno" to the configured source
+                    *import.src = Str {
+                        span: Default::default(),
+                        value: Wtf8Atom::from(import_source),
+                        raw: None,
+                    };
+
+                    // Names the import source doesn't export, e.g. inferno's `Component`
+                    if let Some(unknown) = &self.config.prune_unknown_imports {
+                        let had_specifiers = !import.specifiers.is_empty();
+                        import.specifiers.retain(|spec| match spec {
+                            ImportSpecifier::Named(named) => !unknown.iter().any(|name| *name == imported_name(named)),
+                            _ => true,
+                        });
+                        if had_specifiers && import.specifiers.is_empty() {
+                            continue;
                         }
                     }
-                    false
-                }
-            },
 
-            // Member expressions: obj.method()
-            Expr::Member(member) => self.has_vnode_call(&member.obj),
+                    for spec in &import.specifiers {
+                        if let ImportSpecifier::Namespace(namespace) = spec {
+                            emit_warning(
+                                namespace.span,
+                                &format!(
+                                    "rask-ui: `{}` now refers to \"{}\", which only re-exports part of the Inferno API",
+                                    namespace.local.sym, import_source
+                                ),
+                            );
+                        }
+                    }
 
-            // Unary expressions: !expr, +expr, etc.
-            Expr::Unary(unary) => self.has_vnode_call(&unary.arg),
+                    if let Some(namespace_import) = Self::rewrite_default_specifier(import) {
+                        body.push(item);
+                        body.push(namespace_import);
+                        continue;
+                    }
+                }
+            }
 
-            // JSX/Fragments - already transformed by Inferno plugin, so we won't see these
-            _ => false,
-        }
-    }
+            // Re-exports: `export { render } from "inferno"`, `export * from "inferno"`
+            let reexport_src = match &mut item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport { src: Some(src), .. }))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll { src, .. })) => Some(src),
+                _ => None,
+            };
+            if let Some(src) = reexport_src {
+                let source = src.value.to_string_lossy().into_owned();
+                if let Some(target) = self.config.rewritten_source(&source) {
+                    **src = Str {
+                        span: Default::default(),
+                        value: Wtf8Atom::from(target),
+                        raw: None,
+                    };
+                }
 
-    /// Check if a function body directly returns VNode calls (stateless component)
-    fn is_stateless_component(&self, func: &Function) -> bool {
-        if let Some(body) = &func.body {
-            for stmt in &body.stmts {
-                if let Stmt::Return(ret_stmt) = stmt {
-                    if let Some(ret_arg) = &ret_stmt.arg {
-                        // Check if directly returning VNode (not arrow function)
-                        if self.has_vnode_call(ret_arg) {
-                            // Make sure it's NOT an arrow function
-                            if !matches!(&**ret_arg, Expr::Arrow(_)) {
-                                return true;
-                            }
+                // Names the import source doesn't export are pruned like in imports. `export *`
+                // has no names to prune.
+                let mapped = self.config.import_map.as_ref().is_some_and(|map| map.contains_key(&source));
+                let unknown = self
+                    .config
+                    .prune_unknown_imports
+                    .as_ref()
+                    .filter(|_| !mapped && self.config.rewrites_source(&source));
+                if let Some(unknown) = unknown {
+                    if let ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) = &mut item {
+                        let had_specifiers = !export.specifiers.is_empty();
+                        export.specifiers.retain(|spec| match spec {
+                            ExportSpecifier::Named(named) => !unknown.iter().any(|name| *name == export_name(&named.orig)),
+                            _ => true,
+                        });
+                        if had_specifiers && export.specifiers.is_empty() {
+                            continue;
                         }
                     }
                 }
             }
+
+            body.push(item);
         }
-        false
+
+        module.body = body;
     }
 
-    /// Check if a function body returns an arrow function with VNode calls (stateful component)
-    fn is_rask_component(&self, func: &Function) -> bool {
-        if let Some(body) = &func.body {
-            for stmt in &body.stmts {
-                if let Stmt::Return(ret_stmt) = stmt {
-                    if let Some(ret_arg) = &ret_stmt.arg {
-                        // Check if returning arrow function
-                        if let Expr::Arrow(arrow) = &**ret_arg {
-                            // Check arrow body for VNode calls
-                            match &*arrow.body {
-                                BlockStmtOrExpr::Expr(expr) => {
-                                    if self.has_vnode_call(expr) {
-                                        return true;
-                                    }
-                                }
-                                BlockStmtOrExpr::BlockStmt(block) => {
-                                    if self.block_has_vnode_return(block) {
-                                        return true;
-                                    }
-                                }
-                            }
+    /// Coalesce the value imports from the import source into the first one, so rewritten
+    /// inferno imports and existing rask-ui imports end up in a single statement.
+    ///
+    /// Named specifiers are deduplicated by imported name; an alias binding a different
+    /// local is kept since code refers to it. Type-only, side-effect-only and namespace
+    /// imports (which can't hold named specifiers) are left as they are, as is a second
+    /// default import.
+    fn merge_source_imports(&self, module: &mut Module) {
+        let import_source = self.config.import_source();
+        let is_mergeable = |import: &ImportDecl| {
+            &*import.src.value == import_source
+                && !import.type_only
+                && import.with.is_none()
+                && !import.specifiers.is_empty()
+                && !import
+                    .specifiers
+                    .iter()
+                    .any(|spec| matches!(spec, ImportSpecifier::Namespace(_)))
+        };
+        let has_default = |import: &ImportDecl| {
+            import
+                .specifiers
+                .iter()
+                .any(|spec| matches!(spec, ImportSpecifier::Default(_)))
+        };
+
+        let mut target_index: Option<usize> = None;
+        let mut body = Vec::with_capacity(module.body.len());
+
+        for item in module.body.drain(..) {
+            let import = match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if is_mergeable(&import) => import,
+                item => {
+                    body.push(item);
+                    continue;
+                }
+            };
+            let Some(index) = target_index else {
+                target_index = Some(body.len());
+                body.push(ModuleItem::ModuleDecl(ModuleDecl::Import(import)));
+                continue;
+            };
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(target)) = &mut body[index] else {
+                unreachable!();
+            };
+            if has_default(target) && has_default(&import) {
+                body.push(ModuleItem::ModuleDecl(ModuleDecl::Import(import)));
+                continue;
+            }
+
+            for spec in import.specifiers {
+                match spec {
+                    // The default specifier must come first
+                    ImportSpecifier::Default(_) => target.specifiers.insert(0, spec),
+                    ImportSpecifier::Named(ref named) => {
+                        let duplicate = target.specifiers.iter().any(|existing| {
+                            matches!(existing, ImportSpecifier::Named(existing)
+                                if existing.local.sym == named.local.sym
+                                    && imported_name(existing) == imported_name(named))
+                        });
+                        if !duplicate {
+                            target.specifiers.push(spec);
                         }
                     }
+                    ImportSpecifier::Namespace(_) => unreachable!(),
                 }
             }
         }
-        false
+
+        module.body = body;
     }
 
-    /// Recursively check if a block statement contains any return with VNode calls
-    fn block_has_vnode_return(&self, block: &BlockStmt) -> bool {
-        for stmt in &block.stmts {
-            if self.stmt_has_vnode_return(stmt) {
-                return true;
-            }
+    /// Turn the default specifier of a rewritten inferno import into a namespace import.
+    ///
+    /// The runtime has no default export, so `import Inferno from "inferno"` becomes
+    /// `import * as Inferno from "rask-ui"`. When the import also has named specifiers the
+    /// namespace import can't share the statement, so it is returned as a separate item.
+    fn rewrite_default_specifier(import: &mut ImportDecl) -> Option<ModuleItem> {
+        let index = import
+            .specifiers
+            .iter()
+            .position(|spec| matches!(spec, ImportSpecifier::Default(_)))?;
+        let ImportSpecifier::Default(default) = import.specifiers.remove(index) else {
+            unreachable!();
+        };
+
+        let namespace = ImportSpecifier::Namespace(ImportStarAsSpecifier {
+            span: default.span,
+            local: default.local,
+        });
+
+        if import.specifiers.is_empty() {
+            import.specifiers.push(namespace);
+            return None;
         }
-        false
+
+        Some(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+            span: Default::default(),
+            specifiers: vec![namespace],
+            src: import.src.clone(),
+            type_only: import.type_only,
+            with: import.with.clone(),
+            phase: import.phase,
+        })))
     }
 
-    /// Check if a statement (or nested statements) contains a return with VNode calls
-    fn stmt_has_vnode_return(&self, stmt: &Stmt) -> bool {
-        match stmt {
-            // Direct return statement
-            Stmt::Return(ret) => {
-                if let Some(arg) = &ret.arg {
-                    return self.has_vnode_call(arg);
-                }
-            }
-            // If statement - check both branches
-            Stmt::If(if_stmt) => {
-                // Check consequent block
-                if self.stmt_has_vnode_return(&if_stmt.cons) {
-                    return true;
+    /// Reuse bindings of the runtime exports the user already imports from the configured
+    /// source.
+    ///
+    /// For `import { RaskStatefulComponent as Base } from "rask-ui"` the generated classes
+    /// extend `Base` directly, and `inject_runtime` then finds the import already present.
+    ///
+    /// Imports from sources rewritten to it (`import { createFragment } from "inferno"`)
+    /// count too, since they end up importing from the configured source. Other names are
+    /// left to the rewrite, which may prune them.
+    fn reuse_existing_imports(&mut self, module: &Module) {
+        let import_source = self.config.import_source();
+        let runtime_exports = self.config.runtime_exports();
+        let imports_from_source = |source: &str| {
+            source == import_source
+                || (self.config.rewrite_imports() && self.config.rewritten_source(source) == Some(import_source))
+        };
+
+        for item in &module.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
+                if !imports_from_source(&import.src.value.to_string_lossy()) || import.type_only {
+                    continue;
                 }
-                // Check alternate (else/else if) branch
-                if let Some(alt) = &if_stmt.alt {
-                    if self.stmt_has_vnode_return(alt) {
-                        return true;
+                for spec in &import.specifiers {
+                    if let ImportSpecifier::Namespace(namespace) = spec {
+                        if self.config.namespace_imports() {
+                            self.import_rask_namespace = Some(namespace.local.clone());
+                        }
+                    }
+                    if let ImportSpecifier::Named(named) = spec {
+                        if named.is_type_only {
+                            continue;
+                        }
+                        let imported = match &named.imported {
+                            Some(ModuleExportName::Ident(imported)) => &*imported.sym,
+                            Some(ModuleExportName::Str(_)) => continue,
+                            None => &*named.local.sym,
+                        };
+                        if !runtime_exports.contains(&imported) {
+                            continue;
+                        }
+                        if !self.pending_imports.iter().any(|(name, _)| name == imported) {
+                            self.pending_imports.push((imported.to_string(), named.local.clone()));
+                        }
                     }
                 }
             }
-            // Block statement - recursively check all statements
-            Stmt::Block(block) => {
-                return self.block_has_vnode_return(block);
+        }
+    }
+
+    /// Collect the factories imported from JSX runtimes for `recognizeCreateElement`:
+    /// `import { jsx as _jsx } from "react/jsx-runtime"` binds `_jsx`, and
+    /// `import React from "react"` makes `React.createElement` a factory. A `createElement`
+    /// from anywhere else, e.g. `document.createElement`, is left alone.
+    fn collect_jsx_runtime_imports(&mut self, module: &Module) {
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+                continue;
+            };
+            if import.type_only || !is_jsx_runtime_source(&import.src.value.to_string_lossy()) {
+                continue;
             }
-            // Switch statement - check all cases
-            Stmt::Switch(switch) => {
-                for case in &switch.cases {
-                    for cons_stmt in &case.cons {
-                        if self.stmt_has_vnode_return(cons_stmt) {
-                            return true;
+            for spec in &import.specifiers {
+                match spec {
+                    ImportSpecifier::Named(named) if !named.is_type_only => {
+                        let imported = match &named.imported {
+                            Some(ModuleExportName::Ident(imported)) => &*imported.sym,
+                            Some(ModuleExportName::Str(_)) => continue,
+                            None => &*named.local.sym,
+                        };
+                        if CREATE_ELEMENT_FACTORIES.contains(&imported) {
+                            self.create_element_imports.insert(named.local.to_id());
                         }
                     }
+                    ImportSpecifier::Default(default) => {
+                        self.jsx_runtime_namespaces.insert(default.local.to_id());
+                    }
+                    ImportSpecifier::Namespace(namespace) => {
+                        self.jsx_runtime_namespaces.insert(namespace.local.to_id());
+                    }
+                    _ => {}
                 }
             }
-            // Try-catch-finally - check all blocks
-            Stmt::Try(try_stmt) => {
-                if self.block_has_vnode_return(&try_stmt.block) {
-                    return true;
-                }
-                if let Some(handler) = &try_stmt.handler {
-                    if self.block_has_vnode_return(&handler.body) {
-                        return true;
-                    }
+        }
+    }
+
+    /// Inject the pending runtime imports (base classes, helpers) at the top of the module,
+    /// as a single import merged with any existing one from the configured source
+    fn inject_runtime(&mut self, module: &mut Module) {
+        let import_source = self.config.import_source();
+
+        // Names already imported from the import source, by their imported name. Aliased
+        // imports (`{ RaskStatefulComponent as Base }`) count under the imported name.
+        let mut imported_names: HashSet<&str> = HashSet::new();
+        for item in &module.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
+                if &*import.src.value != import_source {
+                    continue;
                 }
-                if let Some(finalizer) = &try_stmt.finalizer {
-                    if self.block_has_vnode_return(finalizer) {
-                        return true;
+                for spec in &import.specifiers {
+                    if let ImportSpecifier::Named(named) = spec {
+                        match &named.imported {
+                            Some(ModuleExportName::Ident(imported)) => imported_names.insert(&imported.sym),
+                            _ => imported_names.insert(&named.local.sym),
+                        };
                     }
                 }
             }
-            // For/while/do-while loops - check body
-            Stmt::For(for_stmt) => {
-                return self.stmt_has_vnode_return(&for_stmt.body);
-            }
-            Stmt::ForIn(for_in) => {
-                return self.stmt_has_vnode_return(&for_in.body);
-            }
-            Stmt::ForOf(for_of) => {
-                return self.stmt_has_vnode_return(&for_of.body);
-            }
-            Stmt::While(while_stmt) => {
-                return self.stmt_has_vnode_return(&while_stmt.body);
+        }
+
+        // Add the requested imports that aren't there yet
+        let missing: Vec<&(String, Ident)> = self
+            .pending_imports
+            .iter()
+            .filter(|(imported, _)| !imported_names.contains(imported.as_str()))
+            .collect();
+
+        // Generated classes extend their base class at runtime, which a type-only import
+        // doesn't bind
+        if self.config.type_only_imports {
+            let base_classes = [self.config.stateful_base_class(), self.config.stateless_base_class()];
+            let type_only_bases: Vec<&str> = missing
+                .iter()
+                .map(|(imported, _)| imported.as_str())
+                .filter(|imported| base_classes.contains(imported))
+                .collect();
+            if !type_only_bases.is_empty() {
+                warn_type_only_base_class(&type_only_bases.join("`, `"));
             }
-            Stmt::DoWhile(do_while) => {
-                return self.stmt_has_vnode_return(&do_while.body);
+        }
+
+        let specifiers: Vec<ImportSpecifier> = missing
+            .into_iter()
+            .map(|(imported, local)| {
+                ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: Default::default(),
+                    local: local.clone(),
+                    imported: Some(ModuleExportName::Ident(quote_ident!(imported.as_str()).into())),
+                    is_type_only: false,
+                })
+            })
+            .collect();
+
+        // Add `import * as Rask from "rask-ui"` for `importStyle: "namespace"`
+        if let Some(namespace) = &self.import_rask_namespace {
+            let exists = module.body.iter().any(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                    &*import.src.value == import_source
+                        && import.specifiers.iter().any(|spec| {
+                            matches!(spec, ImportSpecifier::Namespace(existing)
+                                if existing.local.to_id() == namespace.to_id())
+                        })
+                }
+                _ => false,
+            });
+            if !exists {
+                if self.config.type_only_imports {
+                    warn_type_only_base_class(&namespace.sym);
+                }
+                let import = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                    span: Default::default(),
+                    specifiers: vec![ImportSpecifier::Namespace(ImportStarAsSpecifier {
+                        span: Default::default(),
+                        local: namespace.clone(),
+                    })],
+                    src: Box::new(Str {
+                        span: Default::default(),
+                        value: Wtf8Atom::from(import_source),
+                        raw: None,
+                    }),
+                    type_only: self.config.type_only_imports,
+                    with: None,
+                    phase: Default::default(),
+                }));
+                let index = Self::import_insertion_index(&module.body);
+                module.body.insert(index, import);
             }
-            // Labeled statement - check the nested statement
-            Stmt::Labeled(labeled) => {
-                return self.stmt_has_vnode_return(&labeled.body);
+        }
+
+        if specifiers.is_empty() {
+            return;
+        }
+
+        // Merge into an existing value import from the same source when there is one.
+        // Namespace imports can't be combined with named specifiers, so skip those.
+        let existing = module.body.iter_mut().find_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import))
+                if &*import.src.value == import_source
+                    && !import.type_only
+                    && !import
+                        .specifiers
+                        .iter()
+                        .any(|spec| matches!(spec, ImportSpecifier::Namespace(_))) =>
+            {
+                Some(import)
             }
-            _ => {}
+            _ => None,
+        });
+
+        if let Some(import) = existing {
+            // With `typeOnlyImports` the merged specifiers are marked inline: `{ h, type Base }`
+            let type_only = self.config.type_only_imports;
+            import.specifiers.extend(specifiers.into_iter().map(|mut spec| {
+                if let ImportSpecifier::Named(named) = &mut spec {
+                    named.is_type_only = type_only;
+                }
+                spec
+            }));
+        } else {
+            let import = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: Default::default(),
+                specifiers,
+                src: Box::new(Str {
+                    span: Default::default(),
+                    value: Wtf8Atom::from(import_source),
+                    raw: None,
+                }),
+                type_only: self.config.type_only_imports,
+                with: None,
+                phase: Default::default(),
+            }));
+
+            let index = Self::import_insertion_index(&module.body);
+            module.body.insert(index, import);
         }
-        false
     }
 
-    /// Transform a function declaration to a RaskStatefulComponent class
-    fn transform_to_stateful_class(&mut self, name: Ident, func: Function) -> Decl {
-        // Ensure we have the RaskStatefulComponent import
-        if self.import_rask_stateful_component.is_none() {
-            self.import_rask_stateful_component = Some(private_ident!("RaskStatefulComponent"));
+    /// Inject `const { RaskStatefulComponent, ... } = require("rask-ui")` into a CommonJS
+    /// script, after its directive prologue. With `importStyle: "namespace"` this is
+    /// `const Rask = require("rask-ui")` instead.
+    fn inject_runtime_require(&mut self, script: &mut Script) {
+        let import_source = self.config.import_source();
+
+        if let Some(namespace) = &self.import_rask_namespace {
+            let require = require_decl(Pat::Ident(namespace.clone().into()), import_source);
+            let index = directive_prologue_len(script.body.iter().map(Some));
+            script.body.insert(index, require);
         }
 
-        let super_class_ident = self.import_rask_stateful_component.as_ref().unwrap().clone();
+        let props: Vec<ObjectPatProp> = self
+            .pending_imports
+            .iter()
+            .map(|(imported, local)| {
+                ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: PropName::Ident(quote_ident!(imported.as_str())),
+                    value: Box::new(Pat::Ident(local.clone().into())),
+                })
+            })
+            .collect();
+
+        if props.is_empty() {
+            return;
+        }
 
-        // Create the class property: setup = function name() { ... }
-        let setup_prop = ClassMember::ClassProp(ClassProp {
+        let pattern = Pat::Object(ObjectPat {
             span: Default::default(),
-            key: PropName::Ident(quote_ident!("setup").into()),
-            value: Some(Box::new(Expr::Fn(FnExpr {
-                ident: Some(name.clone()),
-                function: Box::new(func),
-            }))),
+            props,
+            optional: false,
             type_ann: None,
-            is_static: false,
-            decorators: vec![],
-            accessibility: None,
-            is_abstract: false,
-            is_optional: false,
-            is_override: false,
-            readonly: false,
-            declare: false,
-            definite: false,
         });
+        let require = require_decl(pattern, import_source);
 
-        Decl::Class(ClassDecl {
-            ident: name,
-            declare: false,
-            class: Box::new(Class {
-                span: Default::default(),
-                ctxt: Default::default(),
-                decorators: vec![],
-                body: vec![setup_prop],
-                super_class: Some(Box::new(Expr::Ident(super_class_ident))),
-                is_abstract: false,
-                type_params: None,
-                super_type_params: None,
-                implements: vec![],
-            }),
-        })
+        let index = directive_prologue_len(script.body.iter().map(Some));
+        script.body.insert(index, require);
     }
 
-    /// Transform a function declaration to a RaskStatelessComponent class
-    fn transform_to_stateless_class(&mut self, name: Ident, func: Function) -> Decl {
-        // Ensure we have the RaskStatelessComponent import
-        if self.import_rask_stateless_component.is_none() {
-            self.import_rask_stateless_component = Some(private_ident!("RaskStatelessComponent"));
+    /// Record a transformed component for the summary and the manifest
+    fn count_component(&mut self, kind: ComponentKind, name: Atom) {
+        match kind {
+            ComponentKind::Stateful => self.stateful_count += 1,
+            ComponentKind::Stateless => self.stateless_count += 1,
         }
+        self.component_names.push(name);
+    }
 
-        let super_class_ident = self.import_rask_stateless_component.as_ref().unwrap().clone();
+    /// Append `__raskRegister(import.meta.url)` calling the configured `registerModule`
+    /// function, imported from the import source. Only modules with components get it.
+    fn register_module(&mut self, module: &mut Module) {
+        let Some(register) = self.config.register_module.clone() else {
+            return;
+        };
+        if self.component_names.is_empty() {
+            return;
+        }
 
-        // Create the class property: renderFn = function name() { ... }
-        let render_prop = ClassMember::ClassProp(ClassProp {
+        let url = Expr::Member(MemberExpr {
             span: Default::default(),
-            key: PropName::Ident(quote_ident!("renderFn").into()),
-            value: Some(Box::new(Expr::Fn(FnExpr {
-                ident: Some(name.clone()),
-                function: Box::new(func),
-            }))),
-            type_ann: None,
-            is_static: false,
-            decorators: vec![],
-            accessibility: None,
-            is_abstract: false,
-            is_optional: false,
-            is_override: false,
-            readonly: false,
-            declare: false,
-            definite: false,
-        });
-
-        Decl::Class(ClassDecl {
-            ident: name,
-            declare: false,
-            class: Box::new(Class {
+            obj: Box::new(Expr::MetaProp(MetaPropExpr {
                 span: Default::default(),
-                ctxt: Default::default(),
-                decorators: vec![],
-                body: vec![render_prop],
-                super_class: Some(Box::new(Expr::Ident(super_class_ident))),
-                is_abstract: false,
-                type_params: None,
-                super_type_params: None,
-                implements: vec![],
-            }),
-        })
+                kind: MetaPropKind::ImportMeta,
+            })),
+            prop: MemberProp::Ident(quote_ident!("url")),
+        });
+        let call = Expr::Call(CallExpr {
+            span: Default::default(),
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(self.runtime_expr(register))),
+            args: vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(url),
+            }],
+            type_args: None,
+        });
+        module.body.push(ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+            span: Default::default(),
+            expr: Box::new(call),
+        })));
     }
 
-    /// Create a class expression for variable assignments
-    fn create_component_class_expr(&mut self, name: Ident, func: Function, is_stateful: bool) -> ClassExpr {
-        let super_class_ident = if is_stateful {
-            if self.import_rask_stateful_component.is_none() {
-                self.import_rask_stateful_component = Some(private_ident!("RaskStatefulComponent"));
-            }
-            self.import_rask_stateful_component.as_ref().unwrap().clone()
-        } else {
-            if self.import_rask_stateless_component.is_none() {
-                self.import_rask_stateless_component = Some(private_ident!("RaskStatelessComponent"));
-            }
-            self.import_rask_stateless_component.as_ref().unwrap().clone()
-        };
+    /// Append `export const __raskComponents = ["App", ...]` listing the transformed
+    /// components, for `emitManifestExport`. Omitted when nothing was transformed.
+    fn emit_manifest_export(&self, module: &mut Module) {
+        if !self.config.emit_manifest_export || self.component_names.is_empty() {
+            return;
+        }
 
-        let prop_key = if is_stateful { "setup" } else { "renderFn" };
+        let names = self
+            .component_names
+            .iter()
+            .map(|name| {
+                Some(ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Str(Str {
+                        span: Default::default(),
+                        value: Wtf8Atom::from(&**name),
+                        raw: None,
+                    }))),
+                })
+            })
+            .collect();
 
-        ClassExpr {
-            ident: Some(name.clone()),
-            class: Box::new(Class {
+        module.body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: Default::default(),
+            decl: Decl::Var(Box::new(VarDecl {
                 span: Default::default(),
                 ctxt: Default::default(),
-                decorators: vec![],
-                body: vec![ClassMember::ClassProp(ClassProp {
+                kind: VarDeclKind::Const,
+                declare: false,
+                decls: vec![VarDeclarator {
                     span: Default::default(),
-                    key: PropName::Ident(quote_ident!(prop_key).into()),
-                    value: Some(Box::new(Expr::Fn(FnExpr {
-                        ident: Some(name),
-                        function: Box::new(func),
+                    name: Pat::Ident(quote_ident!("__raskComponents").into()),
+                    init: Some(Box::new(Expr::Array(ArrayLit {
+                        span: Default::default(),
+                        elems: names,
                     }))),
-                    type_ann: None,
-                    is_static: false,
-                    decorators: vec![],
-                    accessibility: None,
-                    is_abstract: false,
-                    is_optional: false,
-                    is_override: false,
-                    readonly: false,
-                    declare: false,
                     definite: false,
-                })],
-                super_class: Some(Box::new(Expr::Ident(super_class_ident))),
-                is_abstract: false,
-                type_params: None,
-                super_type_params: None,
-                implements: vec![],
-            }),
-        }
+                }],
+            })),
+        })));
     }
 
-    /// Convert arrow function to regular function for analysis
-    fn arrow_to_function(&self, arrow: &ArrowExpr) -> Function {
-        // Convert arrow params (Vec<Pat>) to function params (Vec<Param>)
-        let params = arrow
-            .params
-            .iter()
-            .map(|pat| Param {
-                span: Default::default(),
-                decorators: vec![],
-                pat: pat.clone(),
-            })
-            .collect();
+    /// Report the number of transformed components when `verbose` is set, and the
+    /// per-file summary in the configured `summaryFormat`
+    fn report_summary(&self) {
+        if self.config.verbose {
+            emit_note(
+                DUMMY_SP,
+                &format!(
+                    "rask-ui: transformed {} stateful / {} stateless components",
+                    self.stateful_count, self.stateless_count
+                ),
+            );
+        }
 
-        Function {
-            params,
-            decorators: vec![],
-            span: arrow.span,
-            ctxt: Default::default(),
-            body: Some(match &*arrow.body {
-                BlockStmtOrExpr::BlockStmt(block) => block.clone(),
-                BlockStmtOrExpr::Expr(expr) => BlockStmt {
-                    span: Default::default(),
-                    ctxt: Default::default(),
-                    stmts: vec![Stmt::Return(ReturnStmt {
-                        span: Default::default(),
-                        arg: Some(expr.clone()),
-                    })],
-                },
-            }),
-            is_generator: arrow.is_generator,
-            is_async: arrow.is_async,
-            type_params: arrow.type_params.clone(),
-            return_type: arrow.return_type.clone(),
+        let filename = self.filename.as_deref().unwrap_or("<anon>");
+        match self.config.summary_format.as_deref() {
+            Some("text") => emit_note(
+                DUMMY_SP,
+                &format!(
+                    "rask-ui summary: {}: {} stateful, {} stateless, {} ambiguous",
+                    filename,
+                    self.stateful_count,
+                    self.stateless_count,
+                    self.ambiguous_count.get()
+                ),
+            ),
+            Some("json") => emit_note(
+                DUMMY_SP,
+                &serde_json::json!({
+                    "file": filename,
+                    "stateful": self.stateful_count,
+                    "stateless": self.stateless_count,
+                    "ambiguous": self.ambiguous_count.get(),
+                })
+                .to_string(),
+            ),
+            _ => {}
         }
     }
 
-    /// Rewrite imports from "inferno" to the configured import source
-    fn rewrite_inferno_imports(&mut self, module: &mut Module) {
-        let import_source = self
-            .config
-            .import_source
-            .as_ref()
-            .map(|s| s.as_str())
-            .unwrap_or("rask-ui");
+    /// Lift `App.X = ...` statements directly following a generated component class into
+    /// static members of the class, for `liftStaticAssignments`
+    fn lift_static_assignments<T: ClassDeclItem>(&self, items: &mut Vec<T>) {
+        let mut lifted: Vec<T> = Vec::with_capacity(items.len());
 
-        for item in &mut module.body {
-            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
-                if &*import.src.value == "inferno" {
-                    // Rewrite the import source from "inferno" to the configured source
-                    import.src = Box::new(Str {
-                        span: Default::default(),
-                        value: Wtf8Atom::from(import_source),
-                        raw: None,
-                    });
+        for mut item in items.drain(..) {
+            let previous_class = lifted
+                .last_mut()
+                .and_then(ClassDeclItem::class_decl_mut)
+                .filter(|class_decl| self.generated_classes.contains(&class_decl.ident.to_id()));
+            if let (Some(class_decl), Some(stmt)) = (previous_class, item.as_stmt_mut()) {
+                if let Some(member) = take_static_assignment(stmt, &class_decl.ident) {
+                    class_decl.class.body.push(member);
+                    continue;
                 }
             }
+            lifted.push(item);
         }
-    }
 
-    /// Inject the RaskStatefulComponent and/or RaskStatelessComponent imports at the top of the module
-    fn inject_runtime(&mut self, module: &mut Module) {
-        let import_source = self
-            .config
-            .import_source
-            .as_ref()
-            .map(|s| s.as_str())
-            .unwrap_or("rask-ui");
-
-        let mut specifiers = vec![];
-
-        // Add RaskStatefulComponent if needed
-        if let Some(stateful_ident) = &self.import_rask_stateful_component {
-            // Check if import already exists
-            let mut exists = false;
-            for item in &module.body {
-                if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
-                    if &*import.src.value == import_source {
-                        for spec in &import.specifiers {
-                            if let ImportSpecifier::Named(named) = spec {
-                                if let Some(ModuleExportName::Ident(imported)) = &named.imported {
-                                    if &*imported.sym == "RaskStatefulComponent" {
-                                        exists = true;
-                                        break;
-                                    }
-                                } else if &*named.local.sym == "RaskStatefulComponent" {
-                                    exists = true;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        *items = lifted;
+    }
 
-            if !exists {
-                specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: Default::default(),
-                    local: stateful_ident.clone(),
-                    imported: Some(ModuleExportName::Ident(quote_ident!("RaskStatefulComponent").into())),
-                    is_type_only: false,
-                }));
-            }
-        }
+    /// Check if a top-level item declares one of the generated component classes
+    fn is_generated_class(&self, item: &ModuleItem) -> bool {
+        declared_binding(item).is_some_and(|id| self.generated_classes.contains(&id))
+    }
 
-        // Add RaskStatelessComponent if needed
-        if let Some(stateless_ident) = &self.import_rask_stateless_component {
-            // Check if import already exists
-            let mut exists = false;
-            for item in &module.body {
-                if let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item {
-                    if &*import.src.value == import_source {
-                        for spec in &import.specifiers {
-                            if let ImportSpecifier::Named(named) = spec {
-                                if let Some(ModuleExportName::Ident(imported)) = &named.imported {
-                                    if &*imported.sym == "RaskStatelessComponent" {
-                                        exists = true;
-                                        break;
-                                    }
-                                } else if &*named.local.sym == "RaskStatelessComponent" {
-                                    exists = true;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    /// Move generated component classes to just after the imports, for `hoistClasses`.
+    /// Overload signatures of the replaced functions move along, right before their class.
+    ///
+    /// Unlike the functions they replace, classes aren't hoisted, so code running before the
+    /// declaration would hit the temporal dead zone. Declaring a class evaluates its base class,
+    /// which is imported, and its static initializers. Classes with static initializers that
+    /// could read bindings declared before them, like a lifted
+    /// `App.defaultProps = { title: TITLE }`, stay where they are.
+    fn hoist_component_classes(&self, items: &mut Vec<ModuleItem>) {
+        let hoisted: HashSet<Id> = items
+            .iter()
+            .filter(|item| {
+                self.is_generated_class(item) && !declared_class(item).is_some_and(has_eager_static_members)
+            })
+            .filter_map(declared_binding)
+            .collect();
+        let (classes, mut rest): (Vec<_>, Vec<_>) = items.drain(..).partition(|item| {
+            declared_binding(item)
+                .or_else(|| overload_signature(item))
+                .is_some_and(|id| hoisted.contains(&id))
+        });
+        let index = Self::import_insertion_index(&rest);
+        rest.splice(index..index, classes);
+        *items = rest;
+    }
 
-            if !exists {
-                specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
-                    span: Default::default(),
-                    local: stateless_ident.clone(),
-                    imported: Some(ModuleExportName::Ident(quote_ident!("RaskStatelessComponent").into())),
-                    is_type_only: false,
-                }));
+    /// Warn about generated component classes used before their declaration while the
+    /// module is evaluated, which worked for the hoisted functions they replace
+    fn warn_on_forward_references(&self, items: &[ModuleItem]) {
+        for (index, item) in items.iter().enumerate() {
+            let Some(id) = declared_binding(item).filter(|id| self.generated_classes.contains(id)) else {
+                continue;
+            };
+            let mut finder = EagerUsageFinder {
+                id: id.clone(),
+                found: false,
+            };
+            items[..index].visit_with(&mut finder);
+            if finder.found {
+                emit_warning(
+                    item.span(),
+                    &format!(
+                        "rask-ui: `{}` is used before its component class is declared; enable hoistClasses to move it up",
+                        id.0
+                    ),
+                );
             }
         }
+    }
 
-        // Only create import if we have specifiers to add
-        if !specifiers.is_empty() {
-            let import = ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
-                span: Default::default(),
-                specifiers,
-                src: Box::new(Str {
-                    span: Default::default(),
-                    value: Wtf8Atom::from(import_source),
-                    raw: None,
-                }),
-                type_only: false,
-                with: None,
-                phase: Default::default(),
-            }));
-
-            module.body.insert(0, import);
-        }
+    /// Position for a generated import: after the directive prologue (`"use client"`, ...)
+    /// and after the last existing import, so import order is preserved
+    fn import_insertion_index(items: &[ModuleItem]) -> usize {
+        let prologue_len = directive_prologue_len(items.iter().map(ModuleItem::as_stmt));
+        let after_imports = items
+            .iter()
+            .rposition(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))))
+            .map_or(0, |index| index + 1);
+        prologue_len.max(after_imports)
     }
 }
 
@@ -541,144 +3390,298 @@ impl VisitMut for RaskComponentTransform {
     noop_visit_mut_type!();
 
     fn visit_mut_module(&mut self, module: &mut Module) {
+        // Remember every binding in the module so synthesized names don't collide
+        self.used_names = collect_decls::<Id, _>(&*module)
+            .into_iter()
+            .map(|(sym, _)| sym)
+            .collect();
+
+        // Declarations exported later through specifiers count as exported too
+        self.exported_names = module
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) if export.src.is_none() => {
+                    Some(&export.specifiers)
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|spec| match spec {
+                ExportSpecifier::Named(ExportNamedSpecifier {
+                    orig: ModuleExportName::Ident(orig),
+                    ..
+                }) => Some(orig.sym.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // Extend base classes the user already imports instead of importing them again
+        self.reuse_existing_imports(module);
+        self.collect_kept_originals(module);
+        if self.config.recognize_create_element {
+            self.collect_jsx_runtime_imports(module);
+        }
+
         // First visit all items to transform them
         module.visit_mut_children_with(self);
 
+        // Report mode only lists detected components and leaves the module untouched
+        if self.config.report_only {
+            return;
+        }
+
         // Rewrite any "inferno" imports to use the configured import source
-        self.rewrite_inferno_imports(module);
+        if self.config.rewrite_imports() {
+            self.rewrite_inferno_imports(module);
+            self.merge_source_imports(module);
+        }
+
+        self.register_module(module);
 
         // Then inject imports if needed
         self.inject_runtime(module);
+
+        self.emit_manifest_export(module);
+
+        self.report_summary();
+    }
+
+    fn visit_mut_script(&mut self, script: &mut Script) {
+        // Remember every binding in the script so synthesized names don't collide
+        self.used_names = collect_decls::<Id, _>(&*script)
+            .into_iter()
+            .map(|(sym, _)| sym)
+            .collect();
+
+        script.visit_mut_children_with(self);
+
+        // Report mode only lists detected components and leaves the script untouched
+        if self.config.report_only {
+            return;
+        }
+
+        // Scripts can't use ESM imports, so the base classes are required instead
+        self.inject_runtime_require(script);
+
+        self.report_summary();
     }
 
-    fn visit_mut_function(&mut self, func: &mut Function) {
-        // Visit the function body to find nested components
-        if let Some(body) = &mut func.body {
-            for stmt in &mut body.stmts {
-                self.visit_mut_stmt(stmt);
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        let mut new_items = Vec::with_capacity(items.len());
+
+        for mut item in items.drain(..) {
+            // Handle: export default function App() { return () => <div /> }
+            // and: export default () => <div />
+            // These become `class App extends ... {}` followed by `export default App`
+            if let ModuleItem::ModuleDecl(decl) = &mut item {
+                if let Some((mut class_decl, name)) = self.transform_default_export(decl) {
+                    class_decl.visit_mut_children_with(self);
+                    new_items.extend(self.take_hoisted_items(true));
+                    new_items.push(ModuleItem::Stmt(Stmt::Decl(class_decl)));
+                    new_items.push(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
+                        ExportDefaultExpr {
+                            // The class keeps the original span (and its comments)
+                            span: Default::default(),
+                            expr: Box::new(Expr::Ident(name)),
+                        },
+                    )));
+                    new_items.extend(self.take_tag_stmts().into_iter().map(ModuleItem::Stmt));
+                    continue;
+                }
             }
+
+            self.visit_mut_module_item(&mut item);
+            let exported = match &item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(_)) => true,
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => {
+                    self.exported_names.contains(&class_decl.ident.sym)
+                }
+                _ => false,
+            };
+            new_items.extend(self.take_hoisted_items(exported));
+            new_items.push(item);
+            new_items.extend(self.take_tag_stmts().into_iter().map(ModuleItem::Stmt));
+        }
+
+        if self.config.lift_static_assignments {
+            self.lift_static_assignments(&mut new_items);
+        }
+
+        if self.config.hoist_classes {
+            self.hoist_component_classes(&mut new_items);
+        } else {
+            self.warn_on_forward_references(&new_items);
+        }
+
+        *items = new_items;
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        // Classes hoisted out of this list go before their statement and tags after it,
+        // not around the outer one
+        let outer_hoisted = std::mem::take(&mut self.hoisted_stmts);
+        let outer_tagged = std::mem::take(&mut self.tagged_functions);
+        let mut new_stmts = Vec::with_capacity(stmts.len());
+
+        for mut stmt in stmts.drain(..) {
+            self.visit_mut_stmt(&mut stmt);
+            new_stmts.extend(self.take_hoisted_stmts());
+            new_stmts.push(stmt);
+            new_stmts.extend(self.take_tag_stmts());
+        }
+
+        if self.config.lift_static_assignments {
+            self.lift_static_assignments(&mut new_stmts);
         }
-        func.visit_mut_children_with(self);
+
+        *stmts = new_stmts;
+        self.hoisted_stmts = outer_hoisted;
+        self.tagged_functions = outer_tagged;
     }
 
     fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
         match stmt {
             // Handle: function MyComponent() { return () => <div /> }
             Stmt::Decl(Decl::Fn(fn_decl)) => {
-                if self.is_rask_component(&fn_decl.function) {
-                    let name = fn_decl.ident.clone();
-                    let func = (*fn_decl.function).clone();
-                    let class_decl = self.transform_to_stateful_class(name, func);
+                let ctx = DeclContext {
+                    span: fn_decl.function.span,
+                    exported: false,
+                };
+                if let Some(class_decl) = self.transform_fn_decl(fn_decl, ctx) {
                     *stmt = Stmt::Decl(class_decl);
-                    return;
-                } else if self.is_stateless_component(&fn_decl.function) {
-                    let name = fn_decl.ident.clone();
-                    let func = (*fn_decl.function).clone();
-                    let class_decl = self.transform_to_stateless_class(name, func);
-                    *stmt = Stmt::Decl(class_decl);
-                    return;
                 }
             }
 
             // Handle: const MyComponent = () => { return () => <div /> }
             Stmt::Decl(Decl::Var(var_decl)) => {
-                for decl in &mut var_decl.decls {
-                    if let Some(init) = &mut decl.init {
-                        // Check if it's an arrow function
-                        if let Expr::Arrow(arrow) = &**init {
-                            let func = self.arrow_to_function(arrow);
-                            let is_stateful = self.is_rask_component(&func);
-                            let is_stateless = self.is_stateless_component(&func);
-
-                            if is_stateful || is_stateless {
-                                // Get the variable name
-                                if let Pat::Ident(ident_pat) = &decl.name {
-                                    let name = ident_pat.id.clone();
-                                    let class_expr = self.create_component_class_expr(name, func, is_stateful);
-                                    *init = Box::new(Expr::Class(class_expr));
-                                }
-                            }
-                        }
-                    }
+                let ctx = DeclContext {
+                    span: var_decl.span,
+                    exported: false,
+                };
+                if let Some(class_decl) = self.transform_var_decl(var_decl, ctx) {
+                    *stmt = Stmt::Decl(class_decl);
+                }
+            }
+
+            // Handle: module.exports.MyComponent = () => () => <div />
+            Stmt::Expr(ExprStmt { span, expr }) => {
+                if let Expr::Assign(assign) = &mut **expr {
+                    self.transform_commonjs_export(assign, *span);
                 }
             }
 
             _ => {}
         }
 
+        // Visit children (including the bodies of transformed components) to find
+        // nested components. Generated classes are never matched again.
         stmt.visit_mut_children_with(self);
     }
 
     fn visit_mut_module_item(&mut self, item: &mut ModuleItem) {
-        match item {
-            ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))) => {
-                // Check for stateful component first (returns arrow function)
-                if self.is_rask_component(&fn_decl.function) {
-                    let name = fn_decl.ident.clone();
-                    let func = (*fn_decl.function).clone();
-                    let class_decl = self.transform_to_stateful_class(name, func);
-                    *item = ModuleItem::Stmt(Stmt::Decl(class_decl));
-                    return;
-                }
-                // Then check for stateless component (directly returns VNode)
-                else if self.is_stateless_component(&fn_decl.function) {
-                    let name = fn_decl.ident.clone();
-                    let func = (*fn_decl.function).clone();
-                    let class_decl = self.transform_to_stateless_class(name, func);
-                    *item = ModuleItem::Stmt(Stmt::Decl(class_decl));
-                    return;
-                }
-            }
-            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
-                // Handle: export default function() { return () => <div /> }
-                if let DefaultDecl::Fn(fn_expr) = &mut export.decl {
-                    if self.is_rask_component(&fn_expr.function) {
-                        // For default exports, we need to keep it as a function expression
-                        // but transform the pattern internally if needed
-                        // For now, we'll skip transforming default exports
-                        // as they need special handling
-                    } else if self.is_stateless_component(&fn_expr.function) {
-                        // Same for stateless default exports
-                    }
+        // Plain statements are handled by `visit_mut_stmt` when visiting the children, so a
+        // declaration is only ever classified once
+        if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) = item {
+            let ctx = DeclContext {
+                span: export.span,
+                exported: true,
+            };
+            // Handle: export function MyComponent() { return () => <div /> }
+            if let Decl::Fn(fn_decl) = &mut export.decl {
+                if let Some(class_decl) = self.transform_fn_decl(fn_decl, ctx) {
+                    export.decl = class_decl;
                 }
             }
-            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
-                // Handle: export function MyComponent() { return () => <div /> }
-                if let Decl::Fn(fn_decl) = &mut export.decl {
-                    // Check for stateful component first
-                    if self.is_rask_component(&fn_decl.function) {
-                        let name = fn_decl.ident.clone();
-                        let func = (*fn_decl.function).clone();
-                        let class_decl = self.transform_to_stateful_class(name, func);
-                        export.decl = class_decl;
-                        return;
-                    }
-                    // Then check for stateless component
-                    else if self.is_stateless_component(&fn_decl.function) {
-                        let name = fn_decl.ident.clone();
-                        let func = (*fn_decl.function).clone();
-                        let class_decl = self.transform_to_stateless_class(name, func);
-                        export.decl = class_decl;
-                        return;
-                    }
+            // Handle: export const MyComponent = () => () => <div />
+            if let Decl::Var(var_decl) = &mut export.decl {
+                if let Some(class_decl) = self.transform_var_decl(var_decl, ctx) {
+                    export.decl = class_decl;
                 }
             }
-            _ => {}
         }
 
+        // Visit children (including the bodies of transformed components) to find
+        // nested components
         item.visit_mut_children_with(self);
     }
 }
 
+/// Build the rask-ui component transform as a visitor that can be composed with other passes.
+///
+/// Directive comments are not read; use [`RaskComponentTransform::with_comments`] for that.
+pub fn rask_component_transform(config: Config) -> impl VisitMut {
+    RaskComponentTransform::with_config(config)
+}
+
+/// Name of the `importSource` override, as experimental metadata key and environment variable
+const IMPORT_SOURCE_OVERRIDE: &str = "RASK_IMPORT_SOURCE";
+
+/// Read the `importSource` override from the environment. The wasm sandbox plugins run in
+/// has no environment, so there it only comes from the host's metadata.
+#[cfg(not(target_arch = "wasm32"))]
+fn env_import_source() -> Option<String> {
+    std::env::var(IMPORT_SOURCE_OVERRIDE).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn env_import_source() -> Option<String> {
+    None
+}
+
+/// Resolve the `importSource` override: the host's metadata value, or else the environment's.
+/// Empty values don't override anything, so an empty metadata value falls back to the environment.
+fn import_source_override(
+    metadata_value: Option<String>,
+    env_value: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    let non_empty = |source: &String| !source.is_empty();
+    metadata_value.filter(non_empty).or_else(|| env_value().filter(non_empty))
+}
+
 #[plugin_transform]
-pub fn process_transform(mut program: Program, metadata: TransformPluginProgramMetadata) -> Program {
-    let config = serde_json::from_str::<Config>(
-        &metadata
-            .get_transform_plugin_config()
-            .unwrap_or_else(|| "{}".to_string()),
-    )
-    .unwrap_or_default();
-
-    program.visit_mut_with(&mut RaskComponentTransform::new(config));
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let config_json = metadata
+        .get_transform_plugin_config()
+        .unwrap_or_else(|| "{}".to_string());
+    let mut config = Config::from_json(&config_json);
+
+    // `RASK_IMPORT_SOURCE` overrides `importSource` for debugging without editing the build
+    // config: passed by the host as experimental metadata, or read from the environment
+    // where the plugin has one
+    let import_source_override =
+        import_source_override(metadata.get_experimental_context(IMPORT_SOURCE_OVERRIDE), env_import_source);
+    if let Some(import_source) = import_source_override {
+        config.import_source = Some(import_source);
+    }
+
+    let filename = metadata.get_context(&TransformPluginMetadataContextKind::Filename);
+    transform_file(program, config, filename, metadata.comments)
+}
+
+/// Run the transform on the program of `filename`, as the plugin entrypoint does. Files
+/// outside `include` or inside `exclude` are left untouched.
+fn transform_file<C: Comments + 'static>(
+    mut program: Program,
+    config: Config,
+    filename: Option<String>,
+    comments: Option<C>,
+) -> Program {
+    if filename.as_ref().is_some_and(|filename| !config.transforms_file(filename)) {
+        return program;
+    }
+
+    let mut transform = RaskComponentTransform::with_config(config);
+    if let Some(filename) = filename {
+        transform = transform.with_filename(filename);
+    }
+    if let Some(comments) = comments {
+        transform = transform.with_comments(comments);
+    }
+
+    program.visit_mut_with(&mut transform);
     program
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,3346 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use swc_core::common::{comments::SingleThreadedComments, errors::Handler, Mark};
+use swc_core::ecma::{
+    codegen::{text_writer::JsWriter, Emitter},
+    parser::{Syntax, TsSyntax},
+    transforms::{
+        base::{fixer::fixer, hygiene::hygiene, resolver},
+        testing::{test_inline, Tester},
+    },
+    visit::visit_mut_pass,
+};
+
+use super::*;
+
+fn syntax() -> Syntax {
+    Syntax::Typescript(TsSyntax {
+        tsx: true,
+        ..Default::default()
+    })
+}
+
+/// The transform with a JSON plugin config, run on a resolved program like in the plugin
+fn rask(tester: &mut Tester, config: &str) -> impl Pass {
+    let config = Config::from_json(config);
+    (
+        resolver(Mark::new(), Mark::new(), true),
+        visit_mut_pass(RaskComponentTransform::with_config(config).with_comments((*tester.comments).clone())),
+    )
+}
+
+/// Diagnostics emitted by the transform, as printed in the build log
+#[derive(Clone, Default)]
+struct Diagnostics(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Diagnostics {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Run the transform with a JSON plugin config, returning the output (with comments) and the
+/// diagnostics it emitted
+fn transform(config: &str, input: &str) -> (String, String) {
+    Tester::run(|tester| {
+        let diagnostics = Diagnostics::default();
+        let handler = Handler::with_emitter_writer(Box::new(diagnostics.clone()), Some(tester.cm.clone()));
+        let program = HANDLER.set(&handler, || {
+            let tr = rask(tester, config);
+            tester.apply_transform(tr, "input.tsx", syntax(), None, input)
+        })?;
+        let program = program.apply(hygiene()).apply(fixer(Some(&*tester.comments)));
+        let comments = tester.comments.clone();
+        let output = tester.print(&program, &comments);
+        let diagnostics = String::from_utf8(diagnostics.0.lock().unwrap().clone()).unwrap();
+        Ok((output, diagnostics))
+    })
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    arrow_component,
+    r#"
+    import { createVNode } from "inferno";
+    export const App = () => () => createVNode(1, "div");
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    arrow_component_among_declarators,
+    r#"
+    import { createVNode } from "inferno";
+    const helper = 1, Header = () => createVNode(1, "h1"), other = () => 2;
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    const helper = 1, Header = class Header extends RaskStatelessComponent {
+        renderFn = function Header() {
+            return createVNode(1, "h1");
+        };
+    }, other = () => 2;
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    default_export_function,
+    r#"
+    import { createVNode } from "inferno";
+    export default function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export default App;
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    anonymous_default_export_function,
+    r#"
+    import { createVNode } from "inferno";
+    export default function () {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    class DefaultComponent extends RaskStatelessComponent {
+        renderFn = function DefaultComponent() {
+            return createVNode(1, "div");
+        };
+    }
+    export default DefaultComponent;
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    default_export_arrow,
+    r#"
+    import { createVNode } from "inferno";
+    export default () => createVNode(1, "div");
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    class DefaultComponent extends RaskStatelessComponent {
+        renderFn = function DefaultComponent() {
+            return createVNode(1, "div");
+        };
+    }
+    export default DefaultComponent;
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    default_export_arrow_name_collision,
+    r#"
+    import { createVNode } from "inferno";
+    const DefaultComponent = 1;
+    export default () => () => createVNode(1, "div");
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    const DefaultComponent = 1;
+    class DefaultComponent1 extends RaskStatefulComponent {
+        setup = function DefaultComponent1() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export default DefaultComponent1;
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    async_component_awaiting_vnode,
+    r#"
+    import { createVNode } from "inferno";
+    export async function App() {
+        const data = await load();
+        return await createVNode(1, "div", null, data);
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = async function App() {
+            const data = await load();
+            return await createVNode(1, "div", null, data);
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    sequence_return,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return log(), createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return log(), createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    typescript_wrapped_returns,
+    r#"
+    import { createVNode } from "inferno";
+    export function As() {
+        return createVNode(1, "div") as VNode;
+    }
+    export function NonNull() {
+        return createVNode(1, "div")!;
+    }
+    export function Const() {
+        return createVNode(1, "div") as const;
+    }
+    export function Satisfies() {
+        return createVNode(1, "div") satisfies VNode;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class As extends RaskStatelessComponent {
+        renderFn = function As() {
+            return createVNode(1, "div") as VNode;
+        };
+    }
+    export class NonNull extends RaskStatelessComponent {
+        renderFn = function NonNull() {
+            return createVNode(1, "div")!;
+        };
+    }
+    export class Const extends RaskStatelessComponent {
+        renderFn = function Const() {
+            return createVNode(1, "div") as const;
+        };
+    }
+    export class Satisfies extends RaskStatelessComponent {
+        renderFn = function Satisfies() {
+            return createVNode(1, "div") satisfies VNode;
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"statefulBaseClass":"Stateful","statelessBaseClass":"Stateless"}"#),
+    configured_base_classes,
+    r#"
+    import { createVNode } from "inferno";
+    import { Stateful } from "rask-ui";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, Stateful, Stateless as Stateless } from "rask-ui";
+    export class App extends Stateful {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export class Item extends Stateless {
+        renderFn = function Item() {
+            return createVNode(1, "li");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"setupProperty":"init","renderProperty":"not valid"}"#),
+    configured_properties,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        init = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export class Item extends RaskStatelessComponent {
+        renderFn = function Item() {
+            return createVNode(1, "li");
+        };
+    }
+    "#
+);
+
+#[test]
+fn ambiguous_component_warning() {
+    let input = r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        if (loading) return createVNode(1, "p");
+        return () => createVNode(1, "div");
+    }
+    "#;
+
+    let (output, diagnostics) = transform(r#"{"warnOnAmbiguous":true}"#, input);
+    assert!(diagnostics.contains("warning: rask-ui: function returns both VNodes and a render arrow"));
+    assert!(output.contains("export class App extends RaskStatefulComponent"));
+
+    let (_, diagnostics) = transform("{}", input);
+    assert_eq!(diagnostics, "");
+}
+
+#[test]
+fn leading_comments_kept() {
+    let (output, _) = transform(
+        "{}",
+        r#"
+        import { createVNode } from "inferno";
+        /** The app */
+        export function App() {
+            return () => createVNode(1, "div");
+        }
+        // A list item
+        function Item() {
+            return createVNode(1, "li");
+        }
+        "#,
+    );
+    assert!(output.contains("/** The app */ export class App extends"));
+    assert!(output.contains("// A list item\nclass Item extends"));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    nested_component,
+    r#"
+    import { createVNode } from "inferno";
+    export function Outer() {
+        function Inner() {
+            return () => createVNode(1, "div");
+        }
+        return Inner;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export function Outer() {
+        class Inner extends RaskStatefulComponent {
+            setup = function Inner() {
+                return () => createVNode(1, "div");
+            };
+        }
+        return Inner;
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    member_and_computed_factory_calls,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return helper.createVNode(1, "div");
+    }
+    export function Nodes() {
+        return nodes[createVNode(1, "div")];
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return helper.createVNode(1, "div");
+        };
+    }
+    export class Nodes extends RaskStatelessComponent {
+        renderFn = function Nodes() {
+            return nodes[createVNode(1, "div")];
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"forceExports":true}"#),
+    force_exports,
+    r#"
+    import { render } from "./render";
+    export function App() {
+        return () => render();
+    }
+    export const Item = () => render();
+    export function helper() {
+        return render();
+    }
+    function Local() {
+        return render();
+    }
+    "#,
+    r#"
+    import { render } from "./render";
+    import { RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => render();
+        };
+    }
+    export class Item extends RaskStatelessComponent {
+        renderFn = function Item() {
+            return render();
+        };
+    }
+    export function helper() {
+        return render();
+    }
+    function Local() {
+        return render();
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    force_exports_off,
+    r#"
+    import { render } from "./render";
+    export function App() {
+        return () => render();
+    }
+    "#,
+    r#"
+    import { render } from "./render";
+    export function App() {
+        return () => render();
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    ignore_directive,
+    r#"
+    import { createVNode } from "inferno";
+    /* @rask-ignore */
+    export function Helper() {
+        return createVNode(1, "div");
+    }
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export function Helper() {
+        return createVNode(1, "div");
+    }
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    kind_directives,
+    r#"
+    import { createVNode } from "inferno";
+    // @rask-stateful
+    export function App() {
+        return createVNode(1, "div");
+    }
+    /** @rask-stateless */
+    export function Item() {
+        return () => createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    export class Item extends RaskStatelessComponent {
+        renderFn = function Item() {
+            return () => createVNode(1, "li");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    aliased_base_class_import,
+    r#"
+    import { RaskStatefulComponent as Base } from "rask-ui";
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { RaskStatefulComponent as Base, createVNode } from "rask-ui";
+    export class App extends Base {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    merge_into_existing_import,
+    r#"
+    import { createVNode } from "./vnode";
+    import { render } from "rask-ui";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    render(App);
+    "#,
+    r#"
+    import { createVNode } from "./vnode";
+    import { render, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    render(App);
+    "#
+);
+
+#[test]
+fn namespace_inferno_import() {
+    let (output, diagnostics) = transform(
+        "{}",
+        r#"
+        import * as Inferno from "inferno";
+        export function App() {
+            return Inferno.createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(output.contains(r#"import * as Inferno from "rask-ui";"#));
+    assert!(output.contains("return Inferno.createVNode(1, \"div\");"));
+    assert!(diagnostics.contains(
+        r#"warning: rask-ui: `Inferno` now refers to "rask-ui", which only re-exports part of the Inferno API"#
+    ));
+}
+
+#[test]
+fn default_inferno_import() {
+    let (output, _) = transform(
+        "{}",
+        r#"
+        import Inferno, { createVNode } from "inferno";
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(output.contains(r#"import * as Inferno from "rask-ui";"#));
+    assert!(output.contains(r#"import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";"#));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"rewriteFrom":["@my/inferno-fork"]}"#),
+    rewrite_from,
+    r#"
+    import { createVNode } from "@my/inferno-fork";
+    import { render } from "inferno";
+    "#,
+    r#"
+    import { createVNode } from "rask-ui";
+    import { render } from "inferno";
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"importMap":{"inferno-hydrate":"rask-ui/hydrate"}}"#),
+    import_map,
+    r#"
+    import { hydrate } from "inferno-hydrate";
+    import { render } from "inferno";
+    import { x } from "inferno/dist/x";
+    "#,
+    r#"
+    import { hydrate } from "rask-ui/hydrate";
+    import { render } from "rask-ui";
+    import { x } from "inferno/dist/x";
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    generic_component,
+    r#"
+    import { createVNode } from "inferno";
+    export function List<T>(props: Props<T>) {
+        return () => createVNode(1, "ul", null, props.items.map((item: T) => props.render(item)));
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class List extends RaskStatefulComponent {
+        setup = function List<T>(props: Props<T>) {
+            return () => createVNode(1, "ul", null, props.items.map((item: T) => props.render(item)));
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"useMethods":true}"#),
+    generic_component_method,
+    r#"
+    import { createVNode } from "inferno";
+    export function List<T>(props: Props<T>) {
+        return () => createVNode(1, "ul", null, props.items.map((item: T) => props.render(item)));
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class List extends RaskStatefulComponent {
+        setup<T>(props: Props<T>) {
+            return () => createVNode(1, "ul", null, props.items.map((item: T) => props.render(item)));
+        }
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"useMethods":true}"#),
+    use_methods,
+    r#"
+    import { createVNode } from "inferno";
+    export async function App() {
+        return () => createVNode(1, "div");
+    }
+    export function* Item() {
+        yield createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        async setup() {
+            return () => createVNode(1, "div");
+        }
+    }
+    export class Item extends RaskStatelessComponent {
+        *renderFn() {
+            yield createVNode(1, "li");
+        }
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    use_methods_off,
+    r#"
+    import { createVNode } from "inferno";
+    export async function App() {
+        return () => createVNode(1, "div");
+    }
+    export function* Item() {
+        yield createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = async function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export class Item extends RaskStatelessComponent {
+        renderFn = function* Item() {
+            yield createVNode(1, "li");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"useMethods":true}"#),
+    use_methods_keeps_recursive_components_as_fields,
+    r#"
+    import { createVNode } from "inferno";
+    export function Tree(props) {
+        return createVNode(1, "ul", null, props.children.map((child) => Tree(child)));
+    }
+    export function Leaf(props) {
+        return createVNode(1, "li", null, props.label);
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Tree extends RaskStatelessComponent {
+        renderFn = function Tree(props) {
+            return createVNode(1, "ul", null, props.children.map((child)=>Tree(child)));
+        };
+    }
+    export class Leaf extends RaskStatelessComponent {
+        renderFn(props) {
+            return createVNode(1, "li", null, props.label);
+        }
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    vnode_returned_through_local,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        const tree = createVNode(1, "div");
+        return tree;
+    }
+    export function Plain() {
+        const value = compute();
+        return value;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            const tree = createVNode(1, "div");
+            return tree;
+        };
+    }
+    export function Plain() {
+        const value = compute();
+        return value;
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    vnode_local_shadowed_in_block,
+    r#"
+    import { createVNode } from "inferno";
+    export function Count(items) {
+        const view = createVNode(1, "div");
+        if (items) {
+            const view = items.length;
+            return view;
+        }
+        return null;
+    }
+    "#,
+    r#"
+    import { createVNode } from "rask-ui";
+    export function Count(items) {
+        const view = createVNode(1, "div");
+        if (items) {
+            const view = items.length;
+            return view;
+        }
+        return null;
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    render_arrow_conditional_returns,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => {
+            if (x) return createVNode(1, "a");
+            else {
+                return createVNode(1, "b");
+            }
+        };
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => {
+                if (x) return createVNode(1, "a");
+                else {
+                    return createVNode(1, "b");
+                }
+            };
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    guard_clause_return,
+    r#"
+    import { createTextVNode } from "inferno";
+    export function App() {
+        if (loading) return createTextVNode("Loading");
+        return render();
+    }
+    "#,
+    r#"
+    import { createTextVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            if (loading) return createTextVNode("Loading");
+            return render();
+        };
+    }
+    "#
+);
+
+#[test]
+fn report_only_leaves_module_unchanged() {
+    let input = r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export const Item = () => createVNode(1, "li");
+    "#;
+
+    Tester::run(|tester| {
+        let program = tester.apply_transform(resolver(Mark::new(), Mark::new(), true), "input.tsx", syntax(), None, input)?;
+        let config: Config = serde_json::from_str(r#"{"reportOnly":true}"#).unwrap();
+        let reported = program.clone().apply(visit_mut_pass(RaskComponentTransform::with_config(config)));
+        assert_eq!(reported, program);
+        Ok(())
+    });
+
+    let (_, diagnostics) = transform(r#"{"reportOnly":true}"#, input);
+    assert!(diagnostics.contains("note: rask-ui: `App` would become a stateful component"));
+    assert!(diagnostics.contains("note: rask-ui: `Item` would become a stateless component"));
+}
+
+#[test]
+fn component_counts() {
+    let input = r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export const Item = () => createVNode(1, "li");
+    export const Row = () => createVNode(1, "tr");
+    "#;
+
+    Tester::run(|tester| {
+        let mut program = tester.apply_transform(resolver(Mark::new(), Mark::new(), true), "input.tsx", syntax(), None, input)?;
+        let mut transform = RaskComponentTransform::with_config(Config::default());
+        program.visit_mut_with(&mut transform);
+        assert_eq!(transform.stateful_count(), 1);
+        assert_eq!(transform.stateless_count(), 2);
+        Ok(())
+    });
+
+    let (_, diagnostics) = transform(r#"{"verbose":true}"#, input);
+    assert!(diagnostics.contains("note: rask-ui: transformed 1 stateful / 2 stateless components"));
+
+    let (_, diagnostics) = transform("{}", input);
+    assert_eq!(diagnostics, "");
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    create_portal_return,
+    r#"
+    import { createPortal } from "inferno";
+    export function Modal(props) {
+        return createPortal(props.children, document.body);
+    }
+    "#,
+    r#"
+    import { createPortal, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Modal extends RaskStatelessComponent {
+        renderFn = function Modal(props) {
+            return createPortal(props.children, document.body);
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"vnodeFactories":["html"]}"#),
+    tagged_template_return,
+    r#"
+    import { html } from "./html";
+    export function App() {
+        return html`<div/>`;
+    }
+    export function Nested() {
+        return css`${html`<b/>`}`;
+    }
+    export function Plain() {
+        return css`color: red`;
+    }
+    "#,
+    r#"
+    import { html } from "./html";
+    import { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return html`<div/>`;
+        };
+    }
+    export class Nested extends RaskStatelessComponent {
+        renderFn = function Nested() {
+            return css`${html`<b/>`}`;
+        };
+    }
+    export function Plain() {
+        return css`color: red`;
+    }
+    "#
+);
+
+#[test]
+fn large_component() {
+    let statements: String = (0..500).map(|i| format!("const value{i} = compute({i});\n")).collect();
+    let input = format!(
+        "import {{ createVNode }} from \"inferno\";\nexport function App() {{\n{statements}return () => createVNode(1, \"div\", null, value499);\n}}\n"
+    );
+
+    let (output, _) = transform("{}", &input);
+    assert!(output.contains("export class App extends RaskStatefulComponent {\n    setup = function App() {\n"));
+    for i in 0..500 {
+        assert!(output.contains(&format!("        const value{i} = compute({i});\n")));
+    }
+    assert!(output.contains("return ()=>createVNode(1, \"div\", null, value499);"));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    import_after_directive_and_imports,
+    r#"
+    "use client";
+    import { createVNode } from "./vnode";
+    import "./styles.css";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    "use client";
+    import { createVNode } from "./vnode";
+    import "./styles.css";
+    import { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    raw_jsx_components,
+    r#"
+    import { render } from "inferno";
+    export function App() {
+        return () => <div>Hi</div>;
+    }
+    export const List = () => <><li /></>;
+    "#,
+    r#"
+    import { render, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => <div>Hi</div>;
+        };
+    }
+    export class List extends RaskStatelessComponent {
+        renderFn = function List() {
+            return <><li /></>;
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    class_expression_component,
+    r#"
+    import { createVNode } from "inferno";
+    const App = class {
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    const Other = class extends Base {
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    const App = class extends RaskStatelessComponent {
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    const Other = class extends Base {
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    export_specifiers_keep_resolving,
+    r#"
+    import { createVNode } from "inferno";
+    function App() {
+        return () => createVNode(1, "div");
+    }
+    const Item = () => createVNode(1, "li");
+    export { App, Item as default };
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    class Item extends RaskStatelessComponent {
+        renderFn = function Item() {
+            return createVNode(1, "li");
+        };
+    }
+    export { App, Item as default };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"stripPrefix":"Base","stripSuffix":"Component"}"#),
+    strip_affixes,
+    r#"
+    import { createVNode } from "inferno";
+    export function AppComponent() {
+        return () => createVNode(1, "div");
+    }
+    function HeaderComponent() {
+        return createVNode(1, "header");
+    }
+    function BaseComponent() {
+        return createVNode(1, "div");
+    }
+    render(HeaderComponent, BaseComponent);
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export const AppComponent = class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    };
+    const HeaderComponent = class Header extends RaskStatelessComponent {
+        renderFn = function Header() {
+            return createVNode(1, "header");
+        };
+    };
+    class BaseComponent extends RaskStatelessComponent {
+        renderFn = function BaseComponent() {
+            return createVNode(1, "div");
+        };
+    }
+    render(HeaderComponent, BaseComponent);
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    template_literal_return,
+    r#"
+    import { createVNode } from "inferno";
+    export function Ssr() {
+        return `${createVNode(1, "div")}`;
+    }
+    export function Text() {
+        return `${name}`;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Ssr extends RaskStatelessComponent {
+        renderFn = function Ssr() {
+            return `${createVNode(1, "div")}`;
+        };
+    }
+    export function Text() {
+        return `${name}`;
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    object_of_vnodes_return,
+    r#"
+    import { createVNode } from "inferno";
+    export function Slots() {
+        return { header: createVNode(1, "h1"), body: createVNode(1, "p") };
+    }
+    export function Spread() {
+        return { ...createVNode(1, "p") };
+    }
+    export function Config() {
+        return { header: "h1" };
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Slots extends RaskStatelessComponent {
+        renderFn = function Slots() {
+            return { header: createVNode(1, "h1"), body: createVNode(1, "p") };
+        };
+    }
+    export class Spread extends RaskStatelessComponent {
+        renderFn = function Spread() {
+            return { ...createVNode(1, "p") };
+        };
+    }
+    export function Config() {
+        return { header: "h1" };
+    }
+    "#
+);
+
+#[test]
+fn minified_returns() {
+    let (output, _) = transform(
+        "{}",
+        r#"
+        import { createVNode } from "inferno";
+        export function Nullish() {
+            return cached ?? createVNode(1, "div");
+        }
+        export function Or() {
+            return void 0 || createVNode(1, "div");
+        }
+        export function Sequence() {
+            return void x, createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(output.contains("export class Nullish extends RaskStatelessComponent"));
+    assert!(output.contains("export class Or extends RaskStatelessComponent"));
+    assert!(output.contains("export class Sequence extends RaskStatelessComponent"));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"renderFromSetup":true}"#),
+    render_from_setup,
+    r#"
+    import { createVNode } from "inferno";
+    export function App(props) {
+        const count = subscribe(props.count);
+        const user = subscribe(props.user);
+        return () => createVNode(1, "div", null, [count.value, user.value]);
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App(props) {
+            const count = subscribe(props.count);
+            const user = subscribe(props.user);
+            return () => this.renderView(count, user);
+        };
+        renderView(count, user) {
+            return createVNode(1, "div", null, [count.value, user.value]);
+        }
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"renderFromSetup":true}"#),
+    render_from_setup_passes_nested_vars,
+    r#"
+    import { createVNode } from "inferno";
+    export function App(props) {
+        if (props.live) {
+            var label = "live";
+        }
+        try {
+            var extra = load();
+        } catch (err) {}
+        return () => createVNode(1, "div", null, label + extra);
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App(props) {
+            if (props.live) {
+                var label = "live";
+            }
+            try {
+                var extra = load();
+            } catch (err) {}
+            return ()=>this.renderView(label, extra);
+        };
+        renderView(label, extra) {
+            return createVNode(1, "div", null, label + extra);
+        }
+    }
+    "#
+);
+
+#[test]
+fn anonymous_component_names_are_stable() {
+    let input = r#"
+    import { createVNode } from "inferno";
+    export const components = { "my-app": () => createVNode(1, "div"), "my-item": () => createVNode(1, "li") };
+    export default () => createVNode(1, "main");
+    "#;
+    assert_eq!(transform("{}", input), transform("{}", input));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    script_requires_runtime,
+    r#"
+    "use strict";
+    const { createVNode } = require("inferno");
+    function App() {
+        return () => createVNode(1, "div");
+    }
+    module.exports = App;
+    "#,
+    r#"
+    "use strict";
+    const { RaskStatefulComponent: RaskStatefulComponent } = require("rask-ui");
+    const { createVNode } = require("inferno");
+    class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    module.exports = App;
+    "#
+);
+
+#[test]
+fn empty_import_source() {
+    let (output, diagnostics) = transform(
+        r#"{"importSource":"  "}"#,
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(diagnostics.contains("warning: rask-ui: importSource is empty, using `rask-ui`"));
+    assert!(output.contains(r#"import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";"#));
+}
+
+const MIXED_RETURNS: &str = r#"
+import { createVNode } from "inferno";
+export function App() {
+    if (x) return compute();
+    return createVNode(1, "div");
+}
+export function Guarded() {
+    if (!x) return null;
+    if (y) return;
+    return createVNode(1, "div");
+}
+"#;
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    lenient_returns,
+    MIXED_RETURNS,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            if (x) return compute();
+            return createVNode(1, "div");
+        };
+    }
+    export class Guarded extends RaskStatelessComponent {
+        renderFn = function Guarded() {
+            if (!x) return null;
+            if (y) return;
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"strictReturns":true}"#),
+    strict_returns,
+    MIXED_RETURNS,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export function App() {
+        if (x) return compute();
+        return createVNode(1, "div");
+    }
+    export class Guarded extends RaskStatelessComponent {
+        renderFn = function Guarded() {
+            if (!x) return null;
+            if (y) return;
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    render_arrow_in_helper_call,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return track(() => createVNode(1, "div"));
+    }
+    export function Value() {
+        return useMemo(() => computeNumber());
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return track(() => createVNode(1, "div"));
+        };
+    }
+    export function Value() {
+        return useMemo(() => computeNumber());
+    }
+    "#
+);
+
+#[test]
+fn generated_members_map_to_the_component() {
+    let input = "import { createVNode } from \"inferno\";\n\nexport function App() {\n    return () => createVNode(1, \"div\");\n}\n";
+
+    Tester::run(|tester| {
+        let tr = rask(tester, "{}");
+        let program = tester.apply_transform(tr, "input.tsx", syntax(), None, input)?;
+
+        let mut output = vec![];
+        let mut mappings = vec![];
+        Emitter {
+            cfg: Default::default(),
+            cm: tester.cm.clone(),
+            comments: None,
+            wr: JsWriter::new(tester.cm.clone(), "\n", &mut output, Some(&mut mappings)),
+        }
+        .emit_program(&program)
+        .unwrap();
+
+        // Every generated line of the class maps back to the component, not the top of the file
+        let output = String::from_utf8(output).unwrap();
+        for generated in ["export class App", "setup = function App()"] {
+            let line = output.lines().position(|line| line.contains(generated)).unwrap();
+            let original_lines: Vec<usize> = mappings
+                .iter()
+                .filter(|(_, position)| position.line as usize == line)
+                .map(|(pos, _)| tester.cm.lookup_char_pos(*pos).line)
+                .collect();
+            assert!(!original_lines.is_empty(), "`{generated}` is not mapped");
+            assert!(original_lines.iter().all(|&original| original == 3), "`{generated}` maps to {original_lines:?}");
+        }
+        Ok(())
+    });
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    anonymous_component_names,
+    r#"
+    import { createVNode } from "inferno";
+    const Component0 = 1;
+    export const components = {
+        "my-app": () => () => createVNode(1, "div"),
+        "my-item": () => createVNode(1, "li"),
+    };
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    const Component0 = 1;
+    export const components = {
+        "my-app": class Component1 extends RaskStatefulComponent {
+            setup = function Component1() {
+                return () => createVNode(1, "div");
+            };
+        },
+        "my-item": class Component2 extends RaskStatelessComponent {
+            renderFn = function Component2() {
+                return createVNode(1, "li");
+            };
+        },
+    };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    object_registry_components,
+    r#"
+    import { createVNode } from "inferno";
+    export const registry = {
+        App() {
+            return () => createVNode(1, "div");
+        },
+        Item: function () {
+            return createVNode(1, "li");
+        },
+        value: 1,
+    };
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export const registry = {
+        App: class App extends RaskStatefulComponent {
+            setup = function App() {
+                return () => createVNode(1, "div");
+            };
+        },
+        Item: class Item extends RaskStatelessComponent {
+            renderFn = function Item() {
+                return createVNode(1, "li");
+            };
+        },
+        value: 1,
+    };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"rewriteImports":false}"#),
+    rewrite_imports_off,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode } from "inferno";
+    import { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+#[test]
+fn parenthesized_arrow_bodies() {
+    let (output, _) = transform(
+        "{}",
+        r#"
+        import { createVNode } from "inferno";
+        export const App = () => () => (createVNode(1, "div"));
+        export const Item = () => (createVNode(1, "li"));
+        export const registry = {
+            Row: () => (createVNode(1, "tr")),
+            Table: () => () => (createVNode(1, "table")),
+        };
+        "#,
+    );
+    assert!(output.contains("export class App extends RaskStatefulComponent"));
+    assert!(output.contains("export class Item extends RaskStatelessComponent"));
+    assert!(output.contains("Row: class Row extends RaskStatelessComponent"));
+    assert!(output.contains("Table: class Table extends RaskStatefulComponent"));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"unwrapHocs":true}"#),
+    unwrap_hocs,
+    r#"
+    import { createVNode } from "inferno";
+    export const App = observer(() => () => createVNode(1, "div"));
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    class App_ extends RaskStatefulComponent {
+        setup = function App_() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export const App = observer(App_);
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"statelessMode":"function"}"#),
+    stateless_mode_function,
+    r#"
+    import { createVNode } from "inferno";
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    export const Row = () => createVNode(1, "tr");
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    Item.rask = true;
+    export function Row() {
+        return createVNode(1, "tr");
+    }
+    Row.rask = true;
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"statelessMode":"class"}"#),
+    stateless_mode_class,
+    r#"
+    import { createVNode } from "inferno";
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Item extends RaskStatelessComponent {
+        renderFn = function Item() {
+            return createVNode(1, "li");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    new_expression_return,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return new Wrapper(createVNode(1, "div"));
+    }
+    export function Bare() {
+        return new Wrapper;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return new Wrapper(createVNode(1, "div"));
+        };
+    }
+    export function Bare() {
+        return new Wrapper;
+    }
+    "#
+);
+
+const SPREAD_RETURNS: &str = r#"
+import { createVNode } from "inferno";
+export function Mapped() {
+    return [...items.map((item) => createVNode(1, "li", null, item))];
+}
+export function Spread() {
+    return [...getVNodes()];
+}
+"#;
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    spread_returns,
+    SPREAD_RETURNS,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Mapped extends RaskStatelessComponent {
+        renderFn = function Mapped() {
+            return [...items.map((item) => createVNode(1, "li", null, item))];
+        };
+    }
+    export function Spread() {
+        return [...getVNodes()];
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"spreadCallsAsVNodes":true}"#),
+    spread_calls_as_vnodes,
+    SPREAD_RETURNS,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Mapped extends RaskStatelessComponent {
+        renderFn = function Mapped() {
+            return [...items.map((item) => createVNode(1, "li", null, item))];
+        };
+    }
+    export class Spread extends RaskStatelessComponent {
+        renderFn = function Spread() {
+            return [...getVNodes()];
+        };
+    }
+    "#
+);
+
+#[test]
+fn invalid_config() {
+    let (output, diagnostics) = transform(
+        r#"{"importSource": "#,
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(diagnostics.contains("warning: rask-ui: invalid plugin config (EOF while parsing a value"));
+    assert!(output.contains(r#"import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";"#));
+    assert!(output.contains("export class App extends RaskStatelessComponent"));
+}
+
+#[test]
+fn include_and_exclude_globs() {
+    let config = Config::from_json(r#"{"include":["src/**"],"exclude":["**/*.test.tsx","src/legacy/*"]}"#);
+    assert!(config.transforms_file("/home/app/src/App.tsx"));
+    assert!(config.transforms_file("/home/app/src/components/nested/Item.tsx"));
+    assert!(config.transforms_file("C:\\app\\src\\App.tsx"));
+    assert!(!config.transforms_file("/home/app/lib/App.tsx"));
+    assert!(!config.transforms_file("/home/app/src/App.test.tsx"));
+    assert!(!config.transforms_file("/home/app/src/legacy/Old.tsx"));
+    assert!(config.transforms_file("/home/app/src/legacy/nested/Old.tsx"));
+
+    let config = Config::from_json("{}");
+    assert!(config.transforms_file("/home/app/lib/App.tsx"));
+}
+
+#[test]
+fn excluded_files_are_left_untouched() {
+    let config = || Config::from_json(r#"{"exclude":["**/*.test.js"]}"#);
+    Tester::run(|tester| {
+        let program = Program::Module(tester.parse_module(
+            "App.js",
+            r#"export function App() { return () => createVNode(1, "div"); }"#,
+        )?);
+
+        let excluded = transform_file(
+            program.clone(),
+            config(),
+            Some("src/App.test.js".to_string()),
+            None::<SingleThreadedComments>,
+        );
+        assert_eq!(excluded, program);
+
+        let included = transform_file(
+            program.clone(),
+            config(),
+            Some("src/App.js".to_string()),
+            None::<SingleThreadedComments>,
+        );
+        assert_ne!(included, program);
+        Ok(())
+    });
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    many_existing_imports,
+    r#"
+    import { a, b, c, d, e, f, g, h, RaskStatelessComponent as Stateless, i, j } from "rask-ui";
+    import { k, l, m } from "./other";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { a, b, c, d, e, f, g, h, RaskStatelessComponent as Stateless, i, j, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    import { k, l, m } from "./other";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    export class Item extends Stateless {
+        renderFn = function Item() {
+            return createVNode(1, "li");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    merge_inferno_and_runtime_imports,
+    r#"
+    import { createVNode, render } from "inferno";
+    import { render, createVNode as h, RaskStatefulComponent } from "rask-ui";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, render, createVNode as h, RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    use_strict_stays_first,
+    r#"
+    "use strict";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    "use strict";
+    import { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+#[test]
+fn unknown_config_options() {
+    let input = r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#;
+
+    let (output, diagnostics) = transform(r#"{"importsource":"my-ui","verbose":false}"#, input);
+    assert!(diagnostics.contains("warning: rask-ui: unknown config options `importsource` are ignored"));
+    assert!(output.contains(r#"from "rask-ui";"#));
+
+    let (output, diagnostics) = transform(r#"{"importSource":"my-ui"}"#, input);
+    assert_eq!(diagnostics, "");
+    assert!(output.contains(r#"import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "my-ui";"#));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    render_arrow_in_try,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        try {
+            const data = load();
+            return () => createVNode(1, "div", null, data);
+        } catch {
+            return () => createVNode(1, "p");
+        }
+    }
+    export function Finally() {
+        try {
+            init();
+        } finally {
+            return () => createVNode(1, "div");
+        }
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            try {
+                const data = load();
+                return () => createVNode(1, "div", null, data);
+            } catch {
+                return () => createVNode(1, "p");
+            }
+        };
+    }
+    export class Finally extends RaskStatefulComponent {
+        setup = function Finally() {
+            try {
+                init();
+            } finally {
+                return () => createVNode(1, "div");
+            }
+        };
+    }
+    "#
+);
+
+#[test]
+fn forward_reference_warning() {
+    let (_, diagnostics) = transform(
+        "{}",
+        r#"
+        import { createVNode } from "inferno";
+        render(App);
+        export { App };
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(diagnostics.contains(
+        "warning: rask-ui: `App` is used before its component class is declared; enable hoistClasses to move it up"
+    ));
+
+    let (_, diagnostics) = transform(
+        "{}",
+        r#"
+        import { createVNode } from "inferno";
+        export { App };
+        const render = () => mount(App);
+        function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert_eq!(diagnostics, "");
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"hoistClasses":true}"#),
+    hoist_classes,
+    r#"
+    import { createVNode } from "inferno";
+    render(App);
+    export function App(): VNode;
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export function App(): VNode;
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    render(App);
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    generator_component,
+    r#"
+    import { createVNode } from "inferno";
+    export function* App() {
+        yield createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function* App() {
+            yield createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"pruneUnknownImports":["Component","linkEvent"]}"#),
+    prune_unknown_imports,
+    r#"
+    import { Component, createVNode } from "inferno";
+    import { linkEvent } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    create_fragment_with_flags_and_key,
+    r#"
+    import { createFragment, createVNode } from "inferno";
+    export function List({ items }) {
+        return createFragment([...items.map((item) => createVNode(1, "li", null, item, 0, null, item))], 8, "list");
+    }
+    "#,
+    r#"
+    import { createFragment, createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class List extends RaskStatelessComponent {
+        renderFn = function List({ items }) {
+            return createFragment([
+                ...items.map((item)=>createVNode(1, "li", null, item, 0, null, item))
+            ], 8, "list");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"emitDisplayName":true}"#),
+    emit_display_name,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    export default () => createVNode(1, "span");
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+        static displayName = "App";
+    }
+    class DefaultComponent extends RaskStatelessComponent {
+        renderFn = function DefaultComponent() {
+            return createVNode(1, "span");
+        };
+        static displayName = "DefaultComponent";
+    }
+    export default DefaultComponent;
+    "#
+);
+
+const SIDE_EFFECT_RENDER: &str = r#"
+import { createVNode } from "inferno";
+export function App() {
+    const state = {};
+    return () => {
+        doRender(createVNode(1, "div"));
+    };
+}
+"#;
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"detectSideEffectRenders":true}"#),
+    detect_side_effect_renders,
+    SIDE_EFFECT_RENDER,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            const state = {};
+            return ()=>{
+                doRender(createVNode(1, "div"));
+            };
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    side_effect_renders_off,
+    SIDE_EFFECT_RENDER,
+    r#"
+    import { createVNode } from "rask-ui";
+    export function App() {
+        const state = {};
+        return ()=>{
+            doRender(createVNode(1, "div"));
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| (rask(t, "{}"), visit_mut_pass(RaskComponentTransform::with_config(Config::from_json("{}")))),
+    second_pass_is_a_no_op,
+    r#"
+    import { createVNode } from "inferno";
+    export function Counter() {
+        const state = { count: 0 };
+        return () => createVNode(1, "div", null, state.count);
+    }
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Counter extends RaskStatefulComponent {
+        setup = function Counter() {
+            const state = {
+                count: 0
+            };
+            return ()=>createVNode(1, "div", null, state.count);
+        };
+    }
+    export class Label extends RaskStatelessComponent {
+        renderFn = function Label() {
+            return createVNode(1, "span");
+        };
+    }
+    "#
+);
+
+#[test]
+fn type_only_imports() {
+    let (output, diagnostics) = transform(
+        r#"{"typeOnlyImports":true}"#,
+        r#"
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(output.contains(r#"import type { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";"#));
+    assert!(diagnostics.contains("warning: rask-ui: typeOnlyImports imports `RaskStatelessComponent` as a type"));
+
+    // Merged into an existing value import, the specifiers are marked inline
+    let (output, _) = transform(
+        r#"{"typeOnlyImports":true}"#,
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(output.contains(
+        r#"import { createVNode, type RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";"#
+    ));
+
+    let (output, diagnostics) = transform(
+        "{}",
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(output.contains(r#"import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";"#));
+    assert_eq!(diagnostics, "");
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    optional_chain_call_arguments,
+    r#"
+    import { createVNode } from "inferno";
+    export function App({ data }) {
+        return data?.render?.(createVNode(1, "div"));
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App({ data }) {
+            return data?.render?.(createVNode(1, "div"));
+        };
+    }
+    "#
+);
+
+const CREATE_ELEMENT_RETURNS: &str = r#"
+import { createElement } from "react";
+import { jsx as _jsx } from "react/jsx-runtime";
+export function Classic() {
+    return createElement("div");
+}
+export function Automatic() {
+    return _jsx("div", {});
+}
+"#;
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"recognizeCreateElement":true}"#),
+    recognize_create_element,
+    CREATE_ELEMENT_RETURNS,
+    r#"
+    import { createElement } from "react";
+    import { jsx as _jsx } from "react/jsx-runtime";
+    import { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Classic extends RaskStatelessComponent {
+        renderFn = function Classic() {
+            return createElement("div");
+        };
+    }
+    export class Automatic extends RaskStatelessComponent {
+        renderFn = function Automatic() {
+            return _jsx("div", {});
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"recognizeCreateElement":true}"#),
+    recognize_create_element_only_from_jsx_runtimes,
+    r#"
+    import React from "react";
+    export function Namespaced() {
+        return React.createElement("div");
+    }
+    export function Dom() {
+        return document.createElement("div");
+    }
+    export function Local() {
+        const _jsx = (tag) => ({ tag });
+        return _jsx("div");
+    }
+    "#,
+    r#"
+    import React from "react";
+    import { RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Namespaced extends RaskStatelessComponent {
+        renderFn = function Namespaced() {
+            return React.createElement("div");
+        };
+    }
+    export function Dom() {
+        return document.createElement("div");
+    }
+    export function Local() {
+        const _jsx = (tag)=>({
+                tag
+            });
+        return _jsx("div");
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    create_element_not_recognized_by_default,
+    CREATE_ELEMENT_RETURNS,
+    CREATE_ELEMENT_RETURNS
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"emitConstructor":true}"#),
+    emit_constructor,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        constructor(){
+            super(...arguments);
+        }
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"emitConstructor":true}"#),
+    emit_constructor_keeps_existing_constructor,
+    r#"
+    import { createVNode } from "inferno";
+    const App = class {
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    const Other = class {
+        constructor() {
+            super();
+            this.x = 1;
+        }
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    const App = class extends RaskStatelessComponent {
+        constructor(){
+            super(...arguments);
+        }
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    const Other = class extends RaskStatelessComponent {
+        constructor(){
+            super();
+            this.x = 1;
+        }
+        render() {
+            return createVNode(1, "div");
+        }
+    };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    named_function_expressions,
+    r#"
+    import { createVNode } from "inferno";
+    export const App = function App() {
+        return () => createVNode(1, "div");
+    };
+    export const Other = function Inner() {
+        return createVNode(1, "div");
+    };
+    export const Anonymous = function() {
+        return createVNode(1, "div");
+    };
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    }
+    export const Other = class Inner extends RaskStatelessComponent {
+        renderFn = function Inner() {
+            return createVNode(1, "div");
+        };
+    };
+    export class Anonymous extends RaskStatelessComponent {
+        renderFn = function Anonymous() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+#[test]
+fn arrow_component_using_this() {
+    let input = r#"
+    import { createVNode } from "inferno";
+    export const App = () => createVNode(1, "div", null, this.props.title);
+    export const Plain = () => createVNode(1, "div");
+    "#;
+
+    let (output, diagnostics) = transform("{}", input);
+    assert_eq!(
+        diagnostics.matches("warning: rask-ui: arrow component uses `this`").count(),
+        1
+    );
+    assert!(diagnostics.contains("export const App = () =>"));
+    assert!(output.contains("export class App extends RaskStatelessComponent"));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"memoStateless":true,"memoHelper":"memo"}"#),
+    memo_stateless,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent, memo as memo } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    }
+    export class Label extends RaskStatelessComponent {
+        renderFn = memo(function Label() {
+            return createVNode(1, "span");
+        });
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"memoStateless":true,"memoHelper":"memo","useMethods":true}"#),
+    memo_stateless_with_methods,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent, memo as memo } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup() {
+            return ()=>createVNode(1, "div");
+        }
+    }
+    export class Label extends RaskStatelessComponent {
+        renderFn = memo(function Label() {
+            return createVNode(1, "span");
+        });
+    }
+    "#
+);
+
+#[test]
+fn memo_stateless_requires_a_helper() {
+    let input = r#"
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    "#;
+
+    let (output, diagnostics) = transform(r#"{"memoStateless":true}"#, input);
+    assert!(diagnostics.contains("warning: rask-ui: memoStateless needs memoHelper"));
+    assert!(output.contains("renderFn = function Label()"));
+
+    let (output, diagnostics) = transform(r#"{"memoStateless":true,"memoHelper":"my-memo"}"#, input);
+    assert!(diagnostics.contains("warning: rask-ui: invalid memoHelper `my-memo`, memoStateless is off"));
+    assert!(output.contains("renderFn = function Label()"));
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"hoistClasses":true,"liftStaticAssignments":true}"#),
+    hoist_classes_keeps_static_initializers_in_place,
+    r#"
+    import { createVNode } from "inferno";
+    const TITLE = "x";
+    render(Item);
+    export function App(props) {
+        return createVNode(1, "div", null, props.title);
+    }
+    App.defaultProps = { title: TITLE };
+    export function Item() {
+        return () => createVNode(1, "li");
+    }
+    Item.displayName = "Item";
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class Item extends RaskStatefulComponent {
+        setup = function Item() {
+            return () => createVNode(1, "li");
+        };
+        static displayName = "Item";
+    }
+    const TITLE = "x";
+    render(Item);
+    export class App extends RaskStatelessComponent {
+        renderFn = function App(props) {
+            return createVNode(1, "div", null, props.title);
+        };
+        static defaultProps = { title: TITLE };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"liftStaticAssignments":true}"#),
+    lift_default_props,
+    r#"
+    import { createVNode } from "inferno";
+    export function App(props) {
+        return createVNode(1, "div", null, props.title);
+    }
+    App.defaultProps = { title: "Hello" };
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App(props) {
+            return createVNode(1, "div", null, props.title);
+        };
+        static defaultProps = {
+            title: "Hello"
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    commonjs_export_assignments,
+    r#"
+    const { createVNode } = require("inferno");
+    module.exports.App = () => () => createVNode(1, "div");
+    exports.Label = function() {
+        return createVNode(1, "span");
+    };
+    "#,
+    r#"
+    const { RaskStatefulComponent: RaskStatefulComponent, RaskStatelessComponent: RaskStatelessComponent } = require("rask-ui");
+    const { createVNode } = require("inferno");
+    module.exports.App = class App extends RaskStatefulComponent {
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    };
+    exports.Label = class Label extends RaskStatelessComponent {
+        renderFn = function Label() {
+            return createVNode(1, "span");
+        };
+    };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"importStyle":"namespace"}"#),
+    namespace_import_style,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    "#,
+    r#"
+    import { createVNode } from "rask-ui";
+    import * as Rask from "rask-ui";
+    export class App extends Rask.RaskStatefulComponent {
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    }
+    export class Label extends Rask.RaskStatelessComponent {
+        renderFn = function Label() {
+            return createVNode(1, "span");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    iife_return,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return (() => createVNode(1, "div"))();
+    }
+    export function Label() {
+        return (function() { return createVNode(1, "span"); })();
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return (()=>createVNode(1, "div"))();
+        };
+    }
+    export class Label extends RaskStatelessComponent {
+        renderFn = function Label() {
+            return function() {
+                return createVNode(1, "span");
+            }();
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"emitManifestExport":true}"#),
+    manifest_export,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Header() {
+        return createVNode(1, "header");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    }
+    export class Header extends RaskStatelessComponent {
+        renderFn = function Header() {
+            return createVNode(1, "header");
+        };
+    }
+    export const __raskComponents = [
+        "App",
+        "Header"
+    ];
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"emitManifestExport":true}"#),
+    manifest_export_without_components,
+    r#"
+    export function add(a, b) {
+        return a + b;
+    }
+    "#,
+    r#"
+    export function add(a, b) {
+        return a + b;
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    arrow_component_with_setup_statements,
+    r#"
+    import { createVNode } from "inferno";
+    export const App = () => {
+        const x = init();
+        return () => createVNode(1, "div", null, x);
+    };
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            const x = init();
+            return ()=>createVNode(1, "div", null, x);
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"pruneUnknownImports":["Component"]}"#),
+    pruned_imports_stay_pruned,
+    r#"
+    import { Component, createVNode } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    clone_vnode_return,
+    r#"
+    import { cloneVNode } from "inferno";
+    export function Wrapper({ base }) {
+        return cloneVNode(base, { className: "wrapped" });
+    }
+    "#,
+    r#"
+    import { cloneVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Wrapper extends RaskStatelessComponent {
+        renderFn = function Wrapper({ base }) {
+            return cloneVNode(base, {
+                className: "wrapped"
+            });
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    overload_signature_untouched,
+    r#"
+    import { createVNode } from "inferno";
+    export function App(props: { title: string }): VNode;
+    export function App(props: { title: string }) {
+        return createVNode(1, "div", null, props.title);
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export function App(props: {
+        title: string;
+    }): VNode;
+    export class App extends RaskStatelessComponent {
+        renderFn = function App(props: {
+            title: string;
+        }) {
+            return createVNode(1, "div", null, props.title);
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    component_name_pattern,
+    r#"
+    import { createVNode } from "inferno";
+    export function renderItem(item) {
+        return createVNode(1, "li", null, item);
+    }
+    export function Item(item) {
+        return createVNode(1, "li", null, item);
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export function renderItem(item) {
+        return createVNode(1, "li", null, item);
+    }
+    export class Item extends RaskStatelessComponent {
+        renderFn = function Item(item) {
+            return createVNode(1, "li", null, item);
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"componentNamePattern":"^Rask"}"#),
+    custom_component_name_pattern,
+    r#"
+    import { createVNode } from "inferno";
+    export function RaskItem() {
+        return createVNode(1, "li");
+    }
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class RaskItem extends RaskStatelessComponent {
+        renderFn = function RaskItem() {
+            return createVNode(1, "li");
+        };
+    }
+    export function Item() {
+        return createVNode(1, "li");
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    anonymous_commonjs_export_skips_name_pattern,
+    r#"
+    const { createVNode } = require("inferno");
+    module.exports = () => () => createVNode(1, "div");
+    "#,
+    r#"
+    const { RaskStatefulComponent: RaskStatefulComponent } = require("rask-ui");
+    const { createVNode } = require("inferno");
+    module.exports = class DefaultComponent extends RaskStatefulComponent {
+        setup = function DefaultComponent() {
+            return ()=>createVNode(1, "div");
+        };
+    };
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    vnode_call_in_conditional_test,
+    r#"
+    import { createVNode } from "inferno";
+    export function App({ a, b }) {
+        return (createVNode(1, "div"), ok) ? a : b;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App({ a, b }) {
+            return (createVNode(1, "div"), ok) ? a : b;
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"minifyImports":true}"#),
+    minify_imports,
+    r#"
+    import { createVNode } from "inferno";
+    const _a = 1;
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    export function Other() {
+        return createVNode(1, "span");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as _b, RaskStatelessComponent as _c } from "rask-ui";
+    const _a = 1;
+    export class App extends _b {
+        setup = function App() {
+            return ()=>createVNode(1, "div");
+        };
+    }
+    export class Label extends _c {
+        renderFn = function Label() {
+            return createVNode(1, "span");
+        };
+    }
+    export class Other extends _c {
+        renderFn = function Other() {
+            return createVNode(1, "span");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"memoStateless":true,"memoHelper":"memoize","autoFragment":true}"#),
+    pending_imports_merge_into_one_import,
+    r#"
+    import { createVNode } from "inferno";
+    export function List() {
+        return [createVNode(1, "li"), createVNode(1, "li")];
+    }
+    "#,
+    r#"
+    import { createVNode, createFragment as createFragment, RaskStatelessComponent as RaskStatelessComponent, memoize as memoize } from "rask-ui";
+    export class List extends RaskStatelessComponent {
+        renderFn = memoize(function List() {
+            return createFragment([createVNode(1, "li"), createVNode(1, "li")], 0);
+        });
+    }
+    "#
+);
+
+const ARRAY_RETURN: &str = r#"
+import { createVNode } from "inferno";
+export function List() {
+    return [createVNode(1, "li"), createVNode(1, "li")];
+}
+"#;
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"autoFragment":true}"#),
+    auto_fragment,
+    ARRAY_RETURN,
+    r#"
+    import { createVNode, createFragment as createFragment, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class List extends RaskStatelessComponent {
+        renderFn = function List() {
+            return createFragment([
+                createVNode(1, "li"),
+                createVNode(1, "li")
+            ], 0);
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    auto_fragment_off,
+    ARRAY_RETURN,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class List extends RaskStatelessComponent {
+        renderFn = function List() {
+            return [
+                createVNode(1, "li"),
+                createVNode(1, "li")
+            ];
+        };
+    }
+    "#
+);
+
+#[test]
+fn json_summary() {
+    let (_, diagnostics) = transform(
+        r#"{"summaryFormat":"json"}"#,
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            return () => createVNode(1, "div");
+        }
+        export function Label() {
+            return createVNode(1, "span");
+        }
+        export function Both() {
+            if (x) return createVNode(1, "p");
+            return () => createVNode(1, "div");
+        }
+        "#,
+    );
+    let summary = diagnostics
+        .lines()
+        .find_map(|line| line.strip_prefix("note: "))
+        .expect("a summary note");
+    let summary: serde_json::Value = serde_json::from_str(summary).unwrap();
+    assert_eq!(summary["file"], "<anon>");
+    assert_eq!(summary["stateful"], 2);
+    assert_eq!(summary["stateless"], 1);
+    assert_eq!(summary["ambiguous"], 1);
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    reexports_rewritten,
+    r#"
+    export { render, createVNode } from "inferno";
+    export * from "inferno";
+    export { default as App } from "./other";
+    "#,
+    r#"
+    export { render, createVNode } from "rask-ui";
+    export * from "rask-ui";
+    export { default as App } from "./other";
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"pruneUnknownImports":["Component","linkEvent"]}"#),
+    reexports_pruned,
+    r#"
+    export { Component, createVNode } from "inferno";
+    export { linkEvent } from "inferno";
+    export * from "inferno";
+    "#,
+    r#"
+    export { createVNode } from "rask-ui";
+    export * from "rask-ui";
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    async_generator_component,
+    r#"
+    import { createVNode } from "inferno";
+    export async function* Stream() {
+        yield createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Stream extends RaskStatelessComponent {
+        renderFn = async function* Stream() {
+            yield createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"registerModule":"__raskRegister"}"#),
+    register_module,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent, __raskRegister as __raskRegister } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    __raskRegister(import.meta.url);
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"registerModule":"__raskRegister"}"#),
+    register_module_without_components,
+    r#"
+    export const add = (a, b) => a + b;
+    "#,
+    r#"
+    export const add = (a, b) => a + b;
+    "#
+);
+
+#[test]
+fn invalid_register_module() {
+    let (output, diagnostics) = transform(
+        r#"{"registerModule":"my-register"}"#,
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            return createVNode(1, "div");
+        }
+        "#,
+    );
+    assert!(diagnostics.contains("warning: rask-ui: invalid registerModule `my-register`"));
+    assert!(!output.contains("my-register"));
+    assert!(!output.contains("import.meta.url"));
+}
+
+#[test]
+fn render_arrow_comment_moves_to_render_method() {
+    let (output, _) = transform(
+        r#"{"renderFromSetup":true}"#,
+        r#"
+        import { createVNode } from "inferno";
+        export function App() {
+            const state = { count: 0 };
+            // Renders the count
+            return () => createVNode(1, "div", null, state.count);
+        }
+        "#,
+    );
+    assert_eq!(output.matches("// Renders the count").count(), 1);
+    let comment = output.find("// Renders the count").unwrap();
+    assert!(comment > output.find("return ()=>this.renderView(state);").unwrap());
+    assert!(comment < output.find("renderView(state) {").unwrap());
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    destructured_props,
+    r#"
+    import { createVNode } from "inferno";
+    export function Stateful({ items }) {
+        return () => createVNode(1, "ul", null, items);
+    }
+    export function Stateless({ title }) {
+        return createVNode(1, "h1", null, title);
+    }
+    export function Default(x = createVNode(1, "div")) {
+        return x;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Stateful extends RaskStatefulComponent {
+        setup = function Stateful({ items }) {
+            return ()=>createVNode(1, "ul", null, items);
+        };
+    }
+    export class Stateless extends RaskStatelessComponent {
+        renderFn = function Stateless({ title }) {
+            return createVNode(1, "h1", null, title);
+        };
+    }
+    export function Default(x = createVNode(1, "div")) {
+        return x;
+    }
+    "#
+);
+
+const KEEP_ORIGINAL_OUTPUT: &str = r#"
+import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+export function AppImpl() {
+    return ()=>createVNode(1, "div");
+}
+export class App extends RaskStatefulComponent {
+    setup = AppImpl;
+}
+"#;
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"keepOriginal":true}"#),
+    keep_original,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    KEEP_ORIGINAL_OUTPUT
+);
+
+test_inline!(
+    syntax(),
+    |t| (
+        rask(t, r#"{"keepOriginal":true}"#),
+        visit_mut_pass(RaskComponentTransform::with_config(Config::from_json(r#"{"keepOriginal":true}"#)))
+    ),
+    keep_original_second_pass_is_a_no_op,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    KEEP_ORIGINAL_OUTPUT
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"keepOriginal":true}"#),
+    keep_original_of_local_component,
+    r#"
+    import { createVNode } from "inferno";
+    function Label() {
+        return createVNode(1, "span");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    function LabelImpl() {
+        return createVNode(1, "span");
+    }
+    class Label extends RaskStatelessComponent {
+        renderFn = LabelImpl;
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, r#"{"explicitOnly":true}"#),
+    explicit_only,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    /** @rask-stateless */
+    export function Label() {
+        return createVNode(1, "span");
+    }
+    export default () => createVNode(1, "p");
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export function App() {
+        return ()=>createVNode(1, "div");
+    }
+    export class Label extends RaskStatelessComponent {
+        renderFn = function Label() {
+            return createVNode(1, "span");
+        };
+    }
+    export default (()=>createVNode(1, "p"));
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    component_in_top_level_if,
+    r#"
+    import { createVNode } from "inferno";
+    if (process.env.NODE_ENV !== "production") {
+        function Debug() {
+            return createVNode(1, "pre");
+        }
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    if (process.env.NODE_ENV !== "production") {
+        class Debug extends RaskStatelessComponent {
+            renderFn = function Debug() {
+                return createVNode(1, "pre");
+            };
+        }
+    }
+    "#
+);
+
+#[test]
+fn empty_config_uses_the_documented_defaults() {
+    let config = Config::from_json("{}");
+
+    assert_eq!(config.import_source(), "rask-ui");
+    assert_eq!(config.stateful_base_class(), "RaskStatefulComponent");
+    assert_eq!(config.stateless_base_class(), "RaskStatelessComponent");
+    assert_eq!(config.setup_property(), "setup");
+    assert_eq!(config.render_property(), "renderFn");
+    assert_eq!(config.memo_helper(), None);
+    assert!(!config.namespace_imports());
+    assert!(!config.stateless_as_function());
+    assert!(config.rewrite_imports());
+    assert!(config.rewrites_source("inferno"));
+    assert!(!config.rewrites_source("react"));
+    assert_eq!(config.rewritten_source("inferno"), Some("rask-ui"));
+    assert!(DEFAULT_VNODE_FACTORIES.iter().all(|factory| config.is_vnode_factory(factory)));
+    assert!(!config.is_vnode_factory("createElement"));
+    assert_eq!(config.strip_affixes("AppView"), None);
+    assert!(config.transforms_file("src/App.tsx"));
+
+    assert!(!config.warn_on_ambiguous);
+    assert!(!config.force_exports);
+    assert!(!config.use_methods);
+    assert!(!config.report_only);
+    assert!(!config.verbose);
+    assert!(!config.render_from_setup);
+    assert!(!config.strict_returns);
+    assert!(!config.unwrap_hocs);
+    assert!(!config.spread_calls_as_vnodes);
+    assert!(!config.hoist_classes);
+    assert!(!config.emit_display_name);
+    assert!(!config.detect_side_effect_renders);
+    assert!(!config.type_only_imports);
+    assert!(!config.emit_constructor);
+    assert!(!config.memo_stateless);
+    assert!(!config.lift_static_assignments);
+    assert!(!config.emit_manifest_export);
+    assert!(!config.minify_imports);
+    assert!(!config.auto_fragment);
+    assert!(!config.keep_original);
+    assert!(!config.explicit_only);
+    assert!(config.prune_unknown_imports.is_none());
+    assert!(config.import_map.is_none());
+    assert!(config.summary_format.is_none());
+    assert!(config.register_module.is_none());
+    assert!(config.unknown_options.is_empty());
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    render_arrow_with_null_guard,
+    r#"
+    import { createVNode } from "inferno";
+    export function App({ data }) {
+        const state = {};
+        return () => {
+            if (!data) return null;
+            return createVNode(1, "div", null, data);
+        };
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App({ data }) {
+            const state = {};
+            return ()=>{
+                if (!data) return null;
+                return createVNode(1, "div", null, data);
+            };
+        };
+    }
+    "#
+);
+
+#[test]
+fn import_source_override_prefers_metadata() {
+    let env = || Some("rask-ui/env".to_string());
+    assert_eq!(
+        import_source_override(Some("rask-ui/debug".to_string()), env).as_deref(),
+        Some("rask-ui/debug")
+    );
+}
+
+#[test]
+fn import_source_override_falls_back_to_environment() {
+    let env = || Some("rask-ui/env".to_string());
+    assert_eq!(import_source_override(None, env).as_deref(), Some("rask-ui/env"));
+    assert_eq!(
+        import_source_override(Some(String::new()), env).as_deref(),
+        Some("rask-ui/env")
+    );
+    assert_eq!(import_source_override(Some(String::new()), || Some(String::new())), None);
+    assert_eq!(import_source_override(None, || None), None);
+}
+
+test_inline!(
+    syntax(),
+    |t| rask(t, "{}"),
+    logical_and_returns,
+    r#"
+    import { createVNode } from "inferno";
+    export function Banner({ show }) {
+        return show && createVNode(1, "div");
+    }
+    export function Both({ a, b }) {
+        return a && b;
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class Banner extends RaskStatelessComponent {
+        renderFn = function Banner({ show }) {
+            return show && createVNode(1, "div");
+        };
+    }
+    export function Both({ a, b }) {
+        return a && b;
+    }
+    "#
+);
@@ -0,0 +1,60 @@
+use swc_core::common::Mark;
+use swc_core::ecma::{
+    parser::{Syntax, TsSyntax},
+    transforms::{base::resolver, testing::test_inline},
+    visit::visit_mut_pass,
+};
+use swc_plugin_rask_component::{rask_component_transform, Config, RaskComponentTransform};
+
+fn syntax() -> Syntax {
+    Syntax::Typescript(TsSyntax {
+        tsx: true,
+        ..Default::default()
+    })
+}
+
+test_inline!(
+    syntax(),
+    |_| (
+        resolver(Mark::new(), Mark::new(), true),
+        visit_mut_pass(rask_component_transform(Config::default()))
+    ),
+    library_function,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return () => createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatefulComponent as RaskStatefulComponent } from "rask-ui";
+    export class App extends RaskStatefulComponent {
+        setup = function App() {
+            return () => createVNode(1, "div");
+        };
+    }
+    "#
+);
+
+test_inline!(
+    syntax(),
+    |t| (
+        resolver(Mark::new(), Mark::new(), true),
+        visit_mut_pass(RaskComponentTransform::with_config(Config::default()).with_comments((*t.comments).clone()))
+    ),
+    with_config,
+    r#"
+    import { createVNode } from "inferno";
+    export function App() {
+        return createVNode(1, "div");
+    }
+    "#,
+    r#"
+    import { createVNode, RaskStatelessComponent as RaskStatelessComponent } from "rask-ui";
+    export class App extends RaskStatelessComponent {
+        renderFn = function App() {
+            return createVNode(1, "div");
+        };
+    }
+    "#
+);